@@ -0,0 +1,187 @@
+//! Reference engine speaking the Chickenfoot Engine Protocol on stdin/stdout.
+//! See `chicken::protocol` for the messages.
+
+use std::io::{self, BufRead, Write};
+
+use chicken::game::{Action, CoverRule, DrawRule, GameState, Variant};
+use chicken::protocol::{Command, Event, Reply};
+use chicken::search::search;
+
+const DEFAULT_DEPTH: u32 = 3;
+
+struct Engine {
+    state: GameState,
+    depth: u32,
+    draw_rule: DrawRule,
+    cover_rule: CoverRule,
+    variant: Variant,
+}
+
+impl Engine {
+    fn new() -> Self {
+        Self {
+            state: GameState::default(),
+            depth: DEFAULT_DEPTH,
+            draw_rule: DrawRule::default(),
+            cover_rule: CoverRule::default(),
+            variant: Variant::default(),
+        }
+    }
+
+    /// Handle one command. Returns the replies to send, or `None` to exit.
+    fn handle(&mut self, command: Command) -> Option<Vec<Reply>> {
+        let replies = match command {
+            Command::Cep => vec![
+                Reply::Id {
+                    key: "name".to_owned(),
+                    value: format!("chicken-engine {}", env!("CARGO_PKG_VERSION")),
+                },
+                Reply::Id {
+                    key: "author".to_owned(),
+                    value: "bwestley".to_owned(),
+                },
+                Reply::CepOk,
+            ],
+            Command::IsReady => vec![Reply::ReadyOk],
+            Command::NewGame => {
+                self.state = GameState {
+                    draw_rule: self.draw_rule,
+                    cover_rule: self.cover_rule,
+                    variant: self.variant,
+                    ..GameState::default()
+                };
+                vec![]
+            }
+            Command::Rule { name, value } => match self.set_rule(&name, &value) {
+                Some(()) => vec![],
+                None => vec![Reply::Info(format!("ignoring rule {name} {value}"))],
+            },
+            Command::Position(state) => {
+                self.state = GameState {
+                    draw_rule: self.draw_rule,
+                    cover_rule: self.cover_rule,
+                    variant: self.variant,
+                    ..state
+                };
+                vec![]
+            }
+            Command::Event { seat, event } => self.apply(seat, event),
+            Command::Go => self.go(),
+            // `go` answers before the next command is read, so there is never a search to stop.
+            Command::Stop => vec![],
+            Command::Quit => return None,
+        };
+        Some(replies)
+    }
+
+    /// Returns `None` for unknown rules and values.
+    fn set_rule(&mut self, name: &str, value: &str) -> Option<()> {
+        match name {
+            "depth" => self.depth = value.parse().ok()?,
+            "draw" => {
+                self.draw_rule = DrawRule::from_name(value)?;
+                self.state.draw_rule = self.draw_rule;
+            }
+            "cover" => {
+                self.cover_rule = CoverRule::from_name(value)?;
+                self.state.cover_rule = self.cover_rule;
+            }
+            "game" => {
+                self.variant = Variant::from_name(value)?;
+                self.state.variant = self.variant;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn apply(&mut self, seat: u8, event: Event) -> Vec<Reply> {
+        if seat >= self.state.players {
+            return vec![Reply::Info(format!(
+                "Seat {seat} is not at the table of {}.",
+                self.state.players
+            ))];
+        }
+        if seat != self.state.turn {
+            self.state.turn = seat;
+            self.state.draws = 0;
+        }
+        match event {
+            Event::Play(mv) => {
+                // The rules only check this in debug builds.
+                let (min, max) = mv.domino();
+                if self.state.played.has(min, max) {
+                    return vec![Reply::Info(format!(
+                        "Domino {min} {max} has already been played."
+                    ))];
+                }
+                if let Err(text) = self.state.record_play(mv) {
+                    return vec![Reply::Info(text)];
+                }
+            }
+            Event::Draw(domino) => {
+                if let (0, Some((min, max))) = (seat, domino) {
+                    self.state.hand.add(min, max);
+                }
+                self.state.record_draw();
+            }
+            Event::Pass => self.state.record_pass(),
+        }
+        vec![]
+    }
+
+    fn go(&self) -> Vec<Reply> {
+        // The hand is the user's, so search as seat 0 whoever is to move.
+        let state = GameState {
+            turn: 0,
+            ..self.state.clone()
+        };
+        match search(&state, self.depth) {
+            Some(result) => {
+                let pv: Vec<String> = result
+                    .pv
+                    .iter()
+                    .map(|mv| format!("{}-{}", mv.end, mv.open))
+                    .collect();
+                vec![
+                    Reply::Info(format!(
+                        "depth {} nodes {} score {:.1} pv {}",
+                        self.depth,
+                        result.nodes,
+                        result.score,
+                        pv.join(" ")
+                    )),
+                    Reply::BestMove(Action::Play(result.pv[0])),
+                ]
+            }
+            None if self.state.may_draw() => vec![Reply::BestMove(Action::Draw)],
+            None => vec![Reply::BestMove(Action::Pass)],
+        }
+    }
+}
+
+fn main() {
+    let mut engine = Engine::new();
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let replies = match line.parse::<Command>() {
+            Ok(command) => match engine.handle(command) {
+                Some(replies) => replies,
+                None => break,
+            },
+            Err(text) => vec![Reply::Info(text)],
+        };
+        for reply in replies {
+            if writeln!(stdout, "{reply}").is_err() {
+                return;
+            }
+        }
+        if stdout.flush().is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Headless self-play between built-in strategies.
+//!
+//! Usage: `chicken-tournament [--games N] [--seed S] [--draw one|until-playable]
+//! [--game chickenfoot|mexican-train|all-fives] <strategy> <strategy> [...]`
+
+use std::process::ExitCode;
+
+use chicken::game::{DrawRule, Variant};
+use chicken::strategy::STRATEGY_NAMES;
+use chicken::tournament::run;
+
+const USAGE: &str = "Usage: chicken-tournament [--games N] [--seed S] \
+    [--draw one|until-playable] [--game chickenfoot|mexican-train|all-fives] \
+    <strategy> <strategy> [...]";
+
+fn main() -> ExitCode {
+    let mut games = 1000;
+    let mut seed = 0;
+    let mut draw_rule = DrawRule::default();
+    let mut variant = Variant::default();
+    let mut names = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--draw" => match args.next().as_deref().and_then(DrawRule::from_name) {
+                Some(rule) => draw_rule = rule,
+                None => {
+                    eprintln!("--draw needs one or until-playable.\n{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--game" => match args.next().as_deref().and_then(Variant::from_name) {
+                Some(game) => variant = game,
+                None => {
+                    eprintln!("--game needs chickenfoot, mexican-train or all-fives.\n{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--games" => match args.next().and_then(|value| value.parse().ok()) {
+                Some(number) => games = number,
+                None => {
+                    eprintln!("--games needs a number 0-{}.\n{USAGE}", u32::MAX);
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--seed" => match args.next().and_then(|value| value.parse().ok()) {
+                Some(number) => seed = number,
+                None => {
+                    eprintln!("--seed needs a number 0-{}.\n{USAGE}", u64::MAX);
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--help" | "-h" => {
+                println!("{USAGE}\nStrategies: {}", STRATEGY_NAMES.join(", "));
+                return ExitCode::SUCCESS;
+            }
+            _ => names.push(arg),
+        }
+    }
+
+    let result = match run(&names, games, seed, draw_rule, variant) {
+        Ok(result) => result,
+        Err(text) => {
+            eprintln!("{text}\n{USAGE}\nStrategies: {}", STRATEGY_NAMES.join(", "));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "{} {} games, seeds {seed}..{}, {} draw rule, {} blocked",
+        result.games,
+        variant.description(),
+        seed.wrapping_add(u64::from(result.games)),
+        draw_rule.name(),
+        result.blocked
+    );
+    println!();
+    println!(
+        "{:<16} {:>8} {:>17} {:>15}",
+        "strategy", "win %", "95% interval", "pips left"
+    );
+    for standing in &result.standings {
+        let (low, high) = standing.win_rate_interval();
+        println!(
+            "{:<16} {:>8.1} {:>7.1} - {:>5.1} {:>8.1} ± {:<4.1}",
+            standing.name,
+            100.0 * standing.win_rate(),
+            100.0 * low,
+            100.0 * high,
+            standing.mean_pips(),
+            standing.mean_pips_margin()
+        );
+    }
+
+    println!();
+    println!("Head to head: % of games the row strategy kept fewer pips than the column.");
+    print!("{:<16}", "");
+    for standing in &result.standings {
+        print!(" {:>14.14}", standing.name);
+    }
+    println!();
+    for (row, standing) in result.standings.iter().enumerate() {
+        print!("{:<16}", standing.name);
+        for column in 0..result.standings.len() {
+            if row == column {
+                print!(" {:>14}", "-");
+            } else {
+                let percent =
+                    100.0 * f64::from(result.head_to_head[row][column]) / f64::from(games.max(1));
+                print!(" {percent:>14.1}");
+            }
+        }
+        println!();
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,207 @@
+use crate::game::CoverRule;
+use crate::PIP_MAX_USIZE;
+
+/// One domino on the table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Tile {
+    pub min: u8,
+    pub max: u8,
+    /// Pips facing away from the parent, where the next domino goes.
+    pub open: u8,
+    /// Index of the tile this one was played on. `None` for the first double.
+    pub parent: Option<usize>,
+    /// Index of the double whose line or chickenfoot this tile is part of. `None` for the
+    /// first double.
+    pub double: Option<usize>,
+    /// Tiles played on this one.
+    pub children: u8,
+}
+
+impl Tile {
+    /// Pips facing the parent.
+    pub fn end(&self) -> u8 {
+        if self.open == self.min {
+            self.max
+        } else {
+            self.min
+        }
+    }
+
+    pub fn is_double(&self) -> bool {
+        self.min == self.max
+    }
+
+    /// Tiles that can be played on this one: 4 on the first double, 3 toes on any other
+    /// double, and 1 on the end of a line.
+    pub fn capacity(&self) -> u8 {
+        match (self.is_double(), self.parent) {
+            (true, None) => 4,
+            (true, Some(_)) => 3,
+            (false, _) => 1,
+        }
+    }
+}
+
+/// The layout of the table as a tree rooted at the first double.
+#[derive(Clone, Default)]
+pub struct Board {
+    pub tiles: Vec<Tile>,
+    /// All Fives: the first double is a spinner open on all four sides right away, and later
+    /// doubles are laid across their line without toes.
+    pub spinner: bool,
+}
+
+impl Board {
+    /// A table holding only the first double.
+    pub fn new(starting: u8) -> Self {
+        Self {
+            tiles: vec![Tile {
+                min: starting,
+                max: starting,
+                open: starting,
+                parent: None,
+                double: None,
+                children: 0,
+            }],
+            spinner: false,
+        }
+    }
+
+    /// A table holding only the first double as a spinner.
+    pub fn spinner(starting: u8) -> Self {
+        Self {
+            spinner: true,
+            ..Self::new(starting)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Tiles that can be played on tile `index`. See `Tile::capacity`; with a spinner, later
+    /// doubles take only 1.
+    pub fn capacity(&self, index: usize) -> u8 {
+        let tile = &self.tiles[index];
+        if self.spinner && tile.parent.is_some() {
+            1
+        } else {
+            tile.capacity()
+        }
+    }
+
+    /// Doubles still waiting for their lines or toes, oldest first. Only the oldest may be
+    /// played on. Never any with a spinner.
+    pub fn pending_doubles(&self) -> Vec<usize> {
+        if self.spinner {
+            return vec![];
+        }
+        (0..self.tiles.len())
+            .filter(|&index| {
+                let tile = &self.tiles[index];
+                tile.is_double() && tile.children < tile.capacity()
+            })
+            .collect()
+    }
+
+    /// Tiles a domino can be played on right now: the oldest pending double, then every line
+    /// end unless `cover` makes the double come first.
+    pub fn open_ends(&self, cover: CoverRule) -> Vec<usize> {
+        let pending = self.pending_doubles();
+        let mut ends: Vec<usize> = pending.iter().copied().take(1).collect();
+        if ends.is_empty() || cover == CoverRule::InOrder {
+            ends.extend((0..self.tiles.len()).filter(|&index| {
+                !pending.contains(&index) && self.tiles[index].children < self.capacity(index)
+            }));
+        }
+        ends
+    }
+
+    /// First open end showing `pips`, trying the pending double first.
+    pub fn find_end(&self, pips: u8, cover: CoverRule) -> Option<usize> {
+        self.open_ends(cover)
+            .into_iter()
+            .find(|&index| self.tiles[index].open == pips)
+    }
+
+    /// Play the domino `end`-`open` on tile `at`, `end` half first. Returns the new tile's
+    /// index. Did nothing if `Err` is returned.
+    pub fn attach(
+        &mut self,
+        end: u8,
+        open: u8,
+        at: usize,
+        cover: CoverRule,
+    ) -> Result<usize, String> {
+        if !self.open_ends(cover).contains(&at) {
+            return Err("Invalid Move: play on an open end.".to_owned());
+        }
+        let parent = self.tiles[at];
+        if parent.open != end {
+            return Err(format!("Invalid Move: play a {} there.", parent.open));
+        }
+        self.tiles[at].children += 1;
+        self.tiles.push(Tile {
+            min: end.min(open),
+            max: end.max(open),
+            open,
+            parent: Some(at),
+            double: if parent.is_double() {
+                Some(at)
+            } else {
+                parent.double
+            },
+            children: 0,
+        });
+        Ok(self.tiles.len() - 1)
+    }
+
+    /// Count of line ends per pip. Pending doubles are not counted: while one is waiting for
+    /// toes it is in `GameState::doubles` instead. A spinner counts once per open side.
+    pub fn endpoints(&self) -> [u8; PIP_MAX_USIZE + 1] {
+        let pending = self.pending_doubles();
+        let mut endpoints = [0; PIP_MAX_USIZE + 1];
+        for (index, tile) in self.tiles.iter().enumerate() {
+            if !pending.contains(&index) {
+                endpoints[usize::from(tile.open)] += self.capacity(index) - tile.children;
+            }
+        }
+        endpoints
+    }
+
+    /// All Fives: pips showing at the ends of the lines. A double at an end counts both
+    /// halves, and so does the spinner until lines go out both of its long sides.
+    pub fn end_sum(&self) -> u32 {
+        let mut sum = 0;
+        for (index, tile) in self.tiles.iter().enumerate() {
+            let open = u32::from(tile.open);
+            sum += match (tile.is_double(), tile.parent) {
+                (true, None) if tile.children < 2 => 2 * open,
+                (true, None) => 0,
+                _ if tile.children < self.capacity(index) => {
+                    if tile.is_double() {
+                        2 * open
+                    } else {
+                        open
+                    }
+                }
+                _ => 0,
+            };
+        }
+        sum
+    }
+
+    /// Tiles from the first double down to `index`.
+    pub fn path(&self, index: usize) -> Vec<usize> {
+        let mut path = vec![index];
+        while let Some(parent) = self.tiles[*path.last().expect("not empty")].parent {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+}
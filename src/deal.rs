@@ -0,0 +1,98 @@
+use crate::game::DominoSet;
+use crate::rng::Rng;
+use crate::PIP_MAX_U8;
+
+/// Dominoes dealt to each seat for a double-twelve set.
+pub fn tiles_per_player(players: usize) -> usize {
+    match players {
+        ..=4 => 15,
+        5..=6 => 12,
+        _ => 10,
+    }
+}
+
+/// A shuffled and dealt double-twelve set. The same seed and player count always give the
+/// same hands and boneyard order, on every platform and build.
+#[derive(Clone)]
+pub struct Deal {
+    pub seed: u64,
+    pub hands: Vec<DominoSet>,
+    /// Face-down dominoes. Draws take from the back.
+    pub boneyard: Vec<(u8, u8)>,
+}
+
+impl Deal {
+    /// Deal `tiles_per_player(players)` dominoes to each seat.
+    pub fn new(seed: u64, players: usize) -> Self {
+        Self::with_tiles(seed, players, tiles_per_player(players))
+    }
+
+    /// Deal `tiles_each` dominoes to each seat. Seat 0 gets the last `tiles_each` dominoes of
+    /// the shuffled set, seat 1 the `tiles_each` before those, and so on; the rest, in
+    /// shuffled order, is the boneyard.
+    pub fn with_tiles(seed: u64, players: usize, tiles_each: usize) -> Self {
+        let mut boneyard = vec![];
+        for max in 0..=PIP_MAX_U8 {
+            for min in 0..=max {
+                boneyard.push((min, max));
+            }
+        }
+        Rng::new(seed).shuffle(&mut boneyard);
+        let mut hands = vec![DominoSet::default(); players];
+        for hand in &mut hands {
+            for (min, max) in boneyard.split_off(boneyard.len() - tiles_each) {
+                hand.add(min, max);
+            }
+        }
+        Self {
+            seed,
+            hands,
+            boneyard,
+        }
+    }
+
+    /// Random number generator for everything else in a game dealt from this seed, such as
+    /// bots, kept independent of the shuffle.
+    pub fn game_rng(&self) -> Rng {
+        Rng::new(self.seed ^ 0xD1CE_D1CE_D1CE_D1CE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hands(deal: &Deal) -> Vec<Vec<(u8, u8)>> {
+        deal.hands.iter().map(DominoSet::as_vector).collect()
+    }
+
+    #[test]
+    fn same_seed_same_deal() {
+        let (a, b) = (Deal::new(42, 4), Deal::new(42, 4));
+        assert_eq!(hands(&a), hands(&b));
+        assert_eq!(a.boneyard, b.boneyard);
+        assert_ne!(hands(&a), hands(&Deal::new(43, 4)));
+        // Reference SplitMix64 output, so the shuffle cannot drift between builds.
+        assert_eq!(Rng::new(0).next_u64(), 0xE220_A839_7B1D_CDAF);
+    }
+
+    #[test]
+    fn seats_take_from_the_back_of_the_shuffle() {
+        let mut shuffled = vec![];
+        for max in 0..=PIP_MAX_U8 {
+            for min in 0..=max {
+                shuffled.push((min, max));
+            }
+        }
+        Rng::new(7).shuffle(&mut shuffled);
+        let deal = Deal::with_tiles(7, 3, 10);
+        for (seat, hand) in deal.hands.iter().enumerate() {
+            let end = shuffled.len() - 10 * seat;
+            for &(min, max) in &shuffled[end - 10..end] {
+                assert!(hand.has(min, max));
+            }
+            assert_eq!(hand.len(), 10);
+        }
+        assert_eq!(deal.boneyard, shuffled[..shuffled.len() - 30]);
+    }
+}
@@ -0,0 +1,106 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command as Process, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::protocol::{Command, Reply};
+
+/// An external engine process speaking the Chickenfoot Engine Protocol over stdin/stdout.
+pub struct EngineHost {
+    child: Child,
+    stdin: ChildStdin,
+    replies: Receiver<Result<Reply, String>>,
+    /// Name reported by the engine with `id name`. The command line until then.
+    pub name: String,
+    /// `true` once the engine has answered the handshake with `cepok`.
+    pub ready: bool,
+    /// `true` once the engine has closed its stdout, usually by exiting.
+    pub exited: bool,
+}
+
+impl EngineHost {
+    /// Launch `command_line` (program followed by whitespace-separated arguments, e.g.
+    /// `python3 bot.py`) and send the handshake.
+    pub fn launch(command_line: &str) -> io::Result<Self> {
+        let mut parts = command_line.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No engine command."))?;
+        let mut child = Process::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        // Read replies on a separate thread so the interface never blocks on the engine.
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let reply = line
+                    .parse::<Reply>()
+                    .map_err(|error| format!("Engine sent \"{line}\": {error}"));
+                if sender.send(reply).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut host = Self {
+            child,
+            stdin,
+            replies,
+            name: command_line.to_owned(),
+            ready: false,
+            exited: false,
+        };
+        host.send(&Command::Cep)?;
+        Ok(host)
+    }
+
+    /// Send one command to the engine.
+    pub fn send(&mut self, command: &Command) -> io::Result<()> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()
+    }
+
+    /// Collect every reply received since the last call. Handshake replies are also
+    /// applied to `name` and `ready`.
+    pub fn poll(&mut self) -> Vec<Result<Reply, String>> {
+        let mut replies = vec![];
+        loop {
+            match self.replies.try_recv() {
+                Ok(reply) => {
+                    match &reply {
+                        Ok(Reply::Id { key, value }) if key == "name" => {
+                            self.name.clone_from(value)
+                        }
+                        Ok(Reply::CepOk) => self.ready = true,
+                        _ => {}
+                    }
+                    replies.push(reply);
+                }
+                Err(TryRecvError::Empty) => return replies,
+                Err(TryRecvError::Disconnected) => {
+                    self.exited = true;
+                    return replies;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for EngineHost {
+    fn drop(&mut self) {
+        // Ask nicely, then make sure the process does not outlive the interface.
+        let _ = self.send(&Command::Quit);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,906 @@
+use crate::board::Board;
+use crate::deal::tiles_per_player;
+use crate::round::RoundEnd;
+use crate::rules::{AllFives, Chickenfoot, MexicanTrain, Rules};
+use crate::{DOMINO_COUNT, MAX_PLAYERS, PIP_MAX_U8, PIP_MAX_USIZE};
+
+/// Bits of `DominoSet::low` / `DominoSet::high` that correspond to a domino.
+const fn valid_bits(first_max: u32, last_max: u32) -> u128 {
+    let mut bits = 0;
+    let mut max = first_max;
+    while max <= last_max {
+        bits |= ((1 << (max + 1)) - 1) << ((max - first_max) * 16);
+        max += 1;
+    }
+    bits
+}
+const LOW_BITS: u128 = valid_bits(0, 7);
+const HIGH_BITS: u128 = valid_bits(8, PIP_MAX_U8 as u32);
+
+#[derive(Clone, Default)]
+pub struct DominoSet {
+    /*
+    DominoSet is implemented as a bit array (2x u128) to optimize for adding,
+    removing, and testing a domino as well as copying the entire set.
+    max: min
+    low
+     0: 0 . . . . . . . . .  .  .  . . . .
+     1: 0 1 . . . . . . . .  .  .  . . . .
+     2: 0 1 2 . . . . . . .  .  .  . . . .
+     3: 0 1 2 3 . . . . . .  .  .  . . . .
+     4: 0 1 2 3 4 . . . . .  .  .  . . . .
+     5: 0 1 2 3 4 5 . . . .  .  .  . . . .
+     6: 0 1 2 3 4 5 6 . . .  .  .  . . . .
+     7: 0 1 2 3 4 5 6 7 . .  .  .  . . . .
+    high
+     8: 0 1 2 3 4 5 6 7 8 .  .  .  . . . .
+     9: 0 1 2 3 4 5 6 7 8 9  .  .  . . . .
+    10: 0 1 2 3 4 5 6 7 8 9 10  .  . . . .
+    11: 0 1 2 3 4 5 6 7 8 9 10 11  . . . .
+    12: 0 1 2 3 4 5 6 7 8 9 10 11 12 . . .
+     */
+    pub low: u128,
+    pub high: u128,
+}
+
+impl DominoSet {
+    pub fn clear(&mut self) {
+        self.low = 0;
+        self.high = 0;
+    }
+    pub fn fill(&mut self) {
+        self.low = u128::MAX;
+        self.high = u128::MAX;
+    }
+    pub fn has(&self, min: u8, max: u8) -> bool {
+        debug_assert!(min <= max && max <= PIP_MAX_U8);
+        // Check if corresponding bit is set.
+        if max < 8 {
+            (self.low & (1 << (max * 16 + min))) >> (max * 16 + min) != 0
+        } else {
+            (self.high & (1 << ((max - 8) * 16 + min))) >> ((max - 8) * 16 + min) != 0
+        }
+    }
+    pub fn add(&mut self, min: u8, max: u8) {
+        // Set corresponding bit to 1.
+        debug_assert!(min <= max && max <= PIP_MAX_U8);
+        if max < 8 {
+            self.low |= 1 << (max * 16 + min);
+        } else {
+            self.high |= 1 << ((max - 8) * 16 + min);
+        }
+    }
+    pub fn remove(&mut self, min: u8, max: u8) {
+        debug_assert!(min <= max && max <= PIP_MAX_U8);
+        // Set corresponding bit to 0.
+        if max < 8 {
+            self.low &= !(1 << (max * 16 + min));
+        } else {
+            self.high &= !(1 << ((max - 8) * 16 + min));
+        }
+    }
+    pub fn toggle(&mut self, min: u8, max: u8) {
+        debug_assert!(min <= max && max <= PIP_MAX_U8);
+        // Set corresponding bit to 0.
+        if max < 8 {
+            self.low ^= 1 << (max * 16 + min);
+        } else {
+            self.high ^= 1 << ((max - 8) * 16 + min);
+        }
+    }
+    pub fn inverted(&self) -> Self {
+        DominoSet {
+            low: !self.low,
+            high: !self.high,
+        }
+    }
+    pub fn len(&self) -> usize {
+        ((self.low & LOW_BITS).count_ones() + (self.high & HIGH_BITS).count_ones()) as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Total pips on every domino in the set.
+    pub fn pips(&self) -> u32 {
+        self.as_vector()
+            .iter()
+            .map(|&(min, max)| u32::from(min) + u32::from(max))
+            .sum()
+    }
+    /// Number of dominoes in the set with `pips` on either half.
+    pub fn count_pips(&self, pips: u8) -> usize {
+        (0..=PIP_MAX_U8)
+            .filter(|&other| self.has(pips.min(other), pips.max(other)))
+            .count()
+    }
+    pub fn as_vector(&self) -> Vec<(u8, u8)> {
+        // Convert to vector.
+        let mut dominoes: Vec<(u8, u8)> = vec![];
+        let mut bits = self.low;
+        for max in 0..8 {
+            for min in 0..(max + 1) {
+                if bits & 1 != 0 {
+                    dominoes.push((min, max));
+                }
+                bits >>= 1;
+            }
+            bits >>= 15 - max;
+        }
+        bits = self.high;
+        for max in 8..(PIP_MAX_U8 + 1) {
+            for min in 0..(max + 1) {
+                if bits & 1 != 0 {
+                    dominoes.push((min, max));
+                }
+                bits >>= 1;
+            }
+            bits >>= 15 - max;
+        }
+        dominoes
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DoubleDomino {
+    pub pips: u8,
+    pub count: u8,
+    pub first: bool,
+}
+
+impl DoubleDomino {
+    pub fn max_count(&self) -> u8 {
+        if self.first {
+            4
+        } else {
+            3
+        }
+    }
+}
+
+/// House rule for a seat that cannot play.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DrawRule {
+    /// Draw one domino, then play it if possible or pass.
+    #[default]
+    OneThenPass,
+    /// Keep drawing until a domino can be played. Pass only when the boneyard is empty.
+    UntilPlayable,
+}
+
+impl DrawRule {
+    pub const ALL: [DrawRule; 2] = [DrawRule::OneThenPass, DrawRule::UntilPlayable];
+
+    /// Name used in saved games, the engine protocol and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DrawRule::OneThenPass => "one",
+            DrawRule::UntilPlayable => "until-playable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            DrawRule::OneThenPass => "Draw one, then pass",
+            DrawRule::UntilPlayable => "Draw until playable",
+        }
+    }
+}
+
+/// House rule for when a double must be covered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CoverRule {
+    /// Nothing else may be played until the double is covered.
+    #[default]
+    Immediately,
+    /// Other ends stay open while a double is uncovered. Doubles are covered in the order
+    /// they were played.
+    InOrder,
+}
+
+impl CoverRule {
+    pub const ALL: [CoverRule; 2] = [CoverRule::Immediately, CoverRule::InOrder];
+
+    /// Name used by the engine protocol's `rule cover` command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CoverRule::Immediately => "immediately",
+            CoverRule::InOrder => "in-order",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CoverRule::Immediately => "Cover doubles first",
+            CoverRule::InOrder => "Cover doubles in order, play elsewhere meanwhile",
+        }
+    }
+}
+
+/// Game played with the double-twelve set.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Variant {
+    #[default]
+    Chickenfoot,
+    /// A train per seat out of the hub double, plus the public Mexican train.
+    MexicanTrain,
+    /// Scored during play on a spinner whenever the ends add up to a multiple of five.
+    AllFives,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 3] = [
+        Variant::Chickenfoot,
+        Variant::MexicanTrain,
+        Variant::AllFives,
+    ];
+
+    /// Name used by the engine protocol's `rule game` command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variant::Chickenfoot => "chickenfoot",
+            Variant::MexicanTrain => "mexican-train",
+            Variant::AllFives => "all-fives",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|variant| variant.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Variant::Chickenfoot => "Chickenfoot",
+            Variant::MexicanTrain => "Mexican Train",
+            Variant::AllFives => "All Fives",
+        }
+    }
+
+    pub fn rules(&self) -> &'static dyn Rules {
+        match self {
+            Variant::Chickenfoot => &Chickenfoot,
+            Variant::MexicanTrain => &MexicanTrain,
+            Variant::AllFives => &AllFives,
+        }
+    }
+}
+
+/// Mexican Train: a line of dominoes out of the hub.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Train {
+    /// Pips at the open end.
+    pub end: u8,
+    /// The owner could not play, so every seat may play on it.
+    pub marker: bool,
+    /// The end is a double that must be covered before anything else is played.
+    pub double: bool,
+}
+
+/// Where the 13 dominoes with one pip value are, as far as the user can tell.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Suit {
+    pub played: usize,
+    pub hand: usize,
+    /// In other hands or the boneyard.
+    pub unseen: usize,
+    /// Open ends showing the pips.
+    pub ends: u8,
+}
+
+impl Suit {
+    /// Every domino left is in the user's hand, so only the user can extend these ends.
+    pub fn dead(&self) -> bool {
+        self.unseen == 0
+    }
+}
+
+/// What a play from the user's hand leaves the opponents.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Block {
+    /// No opponent can play on any end afterwards.
+    LockOut,
+    /// The pips it exposes can only be used by opponents.
+    OpensForOpponents,
+    Neither,
+}
+
+/// Playing a double from the user's hand now, opening a foot, against holding it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DoubleTiming {
+    pub pips: u8,
+    /// Unseen dominoes that could cover it.
+    pub unseen: usize,
+    /// Other dominoes in the user's hand that could cover it.
+    pub toes: usize,
+    /// Per opponent, the chance it holds nothing to cover it with and must draw.
+    pub stuck: Vec<(u8, f32)>,
+}
+
+impl DoubleTiming {
+    /// Opponents expected to draw if the double is played now.
+    pub fn expected_draws(&self) -> f32 {
+        self.stuck.iter().map(|&(_, chance)| chance).sum()
+    }
+
+    /// Play now if it should cost at least one opponent a draw, or if the user can cover most
+    /// of the foot alone. Otherwise hold it for a better time.
+    pub fn play_now(&self) -> bool {
+        self.expected_draws() >= 1.0 || self.toes >= 2
+    }
+}
+
+#[derive(Clone)]
+pub struct GameState {
+    /// Doubles waiting to be covered, oldest first. A double is removed when its count reaches
+    /// 4 (first) or 3 (not first).
+    pub doubles: Vec<DoubleDomino>,
+    /// Count of endpoints (i.e., where dominoes can be played) with [index] pips
+    pub endpoints: [u8; PIP_MAX_USIZE + 1],
+    /// Dominoes in play
+    pub played: DominoSet,
+    /// Dominoes in the user's hand
+    pub hand: DominoSet,
+    /// Number of seats at the table, including the user
+    pub players: u8,
+    /// Dominoes left in the boneyard
+    pub boneyard: u8,
+    pub draw_rule: DrawRule,
+    pub cover_rule: CoverRule,
+    pub variant: Variant,
+    /// Mexican Train: one train per seat, then the public Mexican train. Empty in Chickenfoot
+    pub trains: Vec<Train>,
+    /// Seat to move. The user is seat 0
+    pub turn: u8,
+    /// Dominoes drawn by the seat to move this turn
+    pub draws: u8,
+    /// Bit mask per seat of pips the seat cannot hold, inferred from passes
+    pub excluded: [u16; MAX_PLAYERS],
+    /// Dominoes held by each seat
+    pub tiles: [u8; MAX_PLAYERS],
+    /// Passes in a row since the last play
+    pub passes: u8,
+    /// Pips of the double this round started with
+    pub starting: u8,
+    /// Pips left over at the end of earlier rounds, per seat
+    pub scores: [u32; MAX_PLAYERS],
+    /// Team of each seat. Seats on the same team play as partners; by default every seat is
+    /// a team of its own
+    pub teams: [u8; MAX_PLAYERS],
+    /// Nobody was dealt the first double, so seats draw in turn until someone finds it
+    pub drawing_for_double: bool,
+    /// Layout of the table. `None` once it is unknown, e.g. after the played dominoes or
+    /// endpoints were edited by hand
+    pub board: Option<Board>,
+}
+
+impl GameState {
+    /// Play a domino. Places the `min` end on the endpoint if `min_matches` is `true`.
+    /// Did nothing if `Err` is returned.
+    pub fn play(&mut self, min: u8, max: u8, min_matches: bool) -> Result<(), String> {
+        let (end, open) = if min_matches { (min, max) } else { (max, min) };
+        self.play_at(Move { end, open }, None)
+    }
+
+    /// Play `mv` on end `at`, a tile of the board or a train in Mexican Train, or on the
+    /// first matching end if `None`. Did nothing if `Err` is returned.
+    pub fn play_at(&mut self, mv: Move, at: Option<usize>) -> Result<(), String> {
+        self.rules().play(self, mv, at)
+    }
+
+    /// Index of the public Mexican train in `trains`.
+    pub fn mexican_train(&self) -> usize {
+        usize::from(self.players)
+    }
+
+    /// Whether `seat` may play on train `train`: its own, the Mexican train, or one with a
+    /// marker.
+    pub fn train_open(&self, seat: u8, train: usize) -> bool {
+        train == usize::from(seat)
+            || train == self.mexican_train()
+            || self.trains.get(train).is_some_and(|train| train.marker)
+    }
+
+    /// Pips showing at tile `at` of the board, or train `at` in Mexican Train.
+    pub fn end_pips(&self, at: usize) -> Option<u8> {
+        self.rules().end_pips(self, at)
+    }
+
+    /// Play a domino described by a `Move`. See `play`.
+    pub fn play_move(&mut self, mv: Move) -> Result<(), String> {
+        self.play_at(mv, None)
+    }
+
+    /// Record that the seat to move played `mv`, taking it from the user's hand if it is the
+    /// user's turn. Did nothing if `Err` is returned.
+    pub fn record_play(&mut self, mv: Move) -> Result<(), String> {
+        self.record_play_at(mv, None)
+    }
+
+    /// `record_play` on tile `at` of the board. See `play_at`.
+    pub fn record_play_at(&mut self, mv: Move, at: Option<usize>) -> Result<(), String> {
+        self.play_at(mv, at)?;
+        if self.turn == 0 {
+            let (min, max) = mv.domino();
+            self.hand.remove(min, max);
+        }
+        let tiles = &mut self.tiles[usize::from(self.turn)];
+        *tiles = tiles.saturating_sub(1);
+        self.passes = 0;
+        if self.rules().plays_again(mv) {
+            self.draws = 0;
+        } else {
+            self.end_turn();
+        }
+        Ok(())
+    }
+
+    /// Every legal way to play a domino from `dominoes`.
+    pub fn legal_moves(&self, dominoes: &DominoSet) -> Vec<Move> {
+        self.rules().legal_moves(self, dominoes)
+    }
+
+    /// Dominoes the user cannot see: in opponents' hands or the boneyard.
+    pub fn unseen(&self) -> DominoSet {
+        let mut unseen = DominoSet::default();
+        for (min, max) in self.played.inverted().as_vector() {
+            if !self.hand.has(min, max) {
+                unseen.add(min, max);
+            }
+        }
+        unseen
+    }
+
+    /// The dominoes with `pips` on either half.
+    pub fn suit(&self, pips: u8) -> Suit {
+        Suit {
+            played: self.played.count_pips(pips),
+            hand: self.hand.count_pips(pips),
+            unseen: self.unseen().count_pips(pips),
+            ends: self.endpoints[usize::from(pips)],
+        }
+    }
+
+    /// Bit mask of pips `seat` may hold: some unseen domino has them and its passes have not
+    /// ruled them out.
+    pub fn may_hold(&self, seat: u8) -> u16 {
+        let unseen = self.unseen();
+        let mask = (0..=PIP_MAX_U8)
+            .filter(|&pips| unseen.count_pips(pips) > 0)
+            .fold(0, |mask, pips| mask | 1 << pips);
+        mask & !self.excluded[usize::from(seat)]
+    }
+
+    /// Seats not on `seat`'s team.
+    pub fn opponents(&self, seat: u8) -> Vec<u8> {
+        (0..self.players)
+            .filter(|&other| !self.partners(seat, other))
+            .collect()
+    }
+
+    /// Weigh playing the `pips` double from the user's hand now. An opponent that passed on
+    /// `pips` surely cannot cover it; otherwise the chance is that of drawing its hand from the
+    /// unseen dominoes without any that match.
+    pub fn double_timing(&self, pips: u8) -> DoubleTiming {
+        let unseen = self.unseen();
+        let total = unseen.len();
+        let matching = unseen.count_pips(pips);
+        let stuck = self
+            .opponents(0)
+            .into_iter()
+            .map(|seat| {
+                if self.may_hold(seat) & 1 << pips == 0 {
+                    return (seat, 1.0);
+                }
+                let tiles = usize::from(self.tiles[usize::from(seat)]).min(total);
+                let chance = (0..tiles)
+                    .map(|drawn| {
+                        total.saturating_sub(matching + drawn) as f32 / (total - drawn) as f32
+                    })
+                    .product();
+                (seat, chance)
+            })
+            .collect();
+        DoubleTiming {
+            pips,
+            unseen: matching,
+            toes: self.hand.count_pips(pips).saturating_sub(1),
+            stuck,
+        }
+    }
+
+    /// Opponents of `seat` down to one or two dominoes, who may go out on their next turn.
+    pub fn danger(&self, seat: u8) -> Vec<u8> {
+        self.opponents(seat)
+            .into_iter()
+            .filter(|&seat| (1..=2).contains(&self.tiles[usize::from(seat)]))
+            .collect()
+    }
+
+    /// What `seat` playing `mv` from `hand` leaves its opponents. `None` if it cannot be
+    /// played.
+    pub fn block(&self, seat: u8, mv: Move) -> Option<Block> {
+        let mut after = self.clone();
+        after.turn = seat;
+        after.record_play(mv).ok()?;
+        let (min, max) = mv.domino();
+        after.hand.remove(min, max);
+        let opponents = after.opponents(seat);
+        let stuck = |&opponent: &u8| {
+            let mut state = after.clone();
+            state.turn = opponent;
+            state.open_pips() & after.may_hold(opponent) == 0
+        };
+        if opponents.iter().all(stuck) {
+            return Some(Block::LockOut);
+        }
+        let open = 1 << mv.open;
+        let ours = after.hand.count_pips(mv.open) > 0
+            || after
+                .team(seat)
+                .into_iter()
+                .any(|partner| partner != seat && after.may_hold(partner) & open != 0);
+        let theirs = opponents
+            .into_iter()
+            .any(|opponent| after.may_hold(opponent) & open != 0);
+        Some(if theirs && !ours {
+            Block::OpensForOpponents
+        } else {
+            Block::Neither
+        })
+    }
+
+    /// Whether seats `a` and `b` are on the same team.
+    pub fn partners(&self, a: u8, b: u8) -> bool {
+        self.teams[usize::from(a)] == self.teams[usize::from(b)]
+    }
+
+    /// Whether any two seats at the table play as partners.
+    pub fn has_teams(&self) -> bool {
+        (0..self.players).any(|a| (0..a).any(|b| self.partners(a, b)))
+    }
+
+    /// Whether the table can split into pairs of partners: an even number of seats, at least
+    /// four.
+    pub fn partnerships_possible(&self) -> bool {
+        self.players >= 4 && self.players.is_multiple_of(2)
+    }
+
+    /// Pair each seat with the one across the table, or give every seat a team of its own
+    /// again. Without `partnerships_possible`, every seat plays for itself.
+    pub fn set_partnerships(&mut self, partnerships: bool) {
+        let partnerships = partnerships && self.partnerships_possible();
+        let half = self.players / 2;
+        for seat in 0..MAX_PLAYERS as u8 {
+            self.teams[usize::from(seat)] = if partnerships { seat % half } else { seat };
+        }
+    }
+
+    /// Seats on `seat`'s team, including itself.
+    pub fn team(&self, seat: u8) -> Vec<u8> {
+        (0..self.players)
+            .filter(|&other| self.partners(seat, other))
+            .collect()
+    }
+
+    /// Combined score of `seat`'s team.
+    pub fn team_score(&self, seat: u8) -> u32 {
+        self.team(seat)
+            .into_iter()
+            .map(|seat| self.scores[usize::from(seat)])
+            .sum()
+    }
+
+    /// Rules of the game being played.
+    pub fn rules(&self) -> &'static dyn Rules {
+        self.variant.rules()
+    }
+
+    /// The double to cover next, if any.
+    pub fn double(&self) -> Option<DoubleDomino> {
+        self.doubles.first().copied()
+    }
+
+    /// Bit mask of pips the seat to move could play on right now.
+    pub fn open_pips(&self) -> u16 {
+        self.rules().open_pips(self)
+    }
+
+    /// The play from the user's hand that scores the most right away, with the end it goes on
+    /// and the points. `None` if nothing in the hand is playable.
+    pub fn best_scoring_play(&self) -> Option<(Move, Option<usize>, u32)> {
+        let mut state = self.clone();
+        state.turn = 0;
+        let ends: Vec<Option<usize>> = match &state.board {
+            Some(board) if board.len() == state.played.len() => board
+                .open_ends(state.cover_rule)
+                .into_iter()
+                .map(Some)
+                .collect(),
+            _ => vec![None],
+        };
+        let mut best: Option<(Move, Option<usize>, u32)> = None;
+        for mv in state.legal_moves(&state.hand) {
+            for &at in &ends {
+                let mut after = state.clone();
+                if after.play_at(mv, at).is_err() {
+                    continue;
+                }
+                let points = after.scores[0] - state.scores[0];
+                if best.is_none_or(|(_, _, most)| points > most) {
+                    best = Some((mv, at, points));
+                }
+            }
+        }
+        best
+    }
+
+    /// Whether the seat to move may draw, assuming it cannot play.
+    pub fn may_draw(&self) -> bool {
+        self.boneyard > 0 && (self.draws == 0 || self.draw_rule == DrawRule::UntilPlayable)
+    }
+
+    /// Whether the seat to move may pass, assuming it cannot play.
+    pub fn may_pass(&self) -> bool {
+        self.boneyard == 0 || (self.draws > 0 && self.draw_rule == DrawRule::OneThenPass)
+    }
+
+    /// Record that the seat to move drew. Whatever it drew may undo earlier pass inferences.
+    pub fn record_draw(&mut self) {
+        self.boneyard = self.boneyard.saturating_sub(1);
+        self.draws = self.draws.saturating_add(1);
+        self.excluded[usize::from(self.turn)] = 0;
+        let tiles = &mut self.tiles[usize::from(self.turn)];
+        *tiles = tiles.saturating_add(1);
+    }
+
+    /// Record that the seat to move passed: it holds nothing that fits.
+    pub fn record_pass(&mut self) {
+        self.excluded[usize::from(self.turn)] |= self.open_pips();
+        self.passes = self.passes.saturating_add(1);
+        self.rules().pass(self);
+        self.end_turn();
+    }
+
+    /// Move on to the next seat.
+    pub fn end_turn(&mut self) {
+        self.turn = (self.turn + 1) % self.players.max(1);
+        self.draws = 0;
+    }
+
+    /// What the seat to move should do next under `draw_rule`.
+    pub fn turn_prompt(&self) -> String {
+        if self.turn == 0 {
+            return if !self.legal_moves(&self.hand).is_empty() {
+                "Your turn: play a domino.".to_owned()
+            } else if self.may_draw() {
+                "Your turn: nothing fits, draw a domino.".to_owned()
+            } else if self.may_pass() {
+                "Your turn: nothing fits, pass.".to_owned()
+            } else {
+                "Your turn: play the domino you drew.".to_owned()
+            };
+        }
+        let seat = self.turn;
+        match (self.draw_rule, self.draws, self.boneyard) {
+            (_, _, 0) => format!("Seat {seat}: play or pass."),
+            (DrawRule::OneThenPass, 0, _) => format!("Seat {seat}: play, or draw one domino."),
+            (DrawRule::OneThenPass, _, _) => {
+                format!("Seat {seat} drew: play the drawn domino or pass.")
+            }
+            (DrawRule::UntilPlayable, draws, _) => {
+                format!("Seat {seat}: play, or keep drawing until a domino fits ({draws} drawn).")
+            }
+        }
+    }
+
+    /// Reset the boneyard and every seat's dominoes to what they are right after dealing to
+    /// `players`.
+    pub fn deal_boneyard(&mut self) {
+        let each = tiles_per_player(usize::from(self.players));
+        self.boneyard = DOMINO_COUNT.saturating_sub(usize::from(self.players) * each) as u8;
+        self.tiles = [each as u8; MAX_PLAYERS];
+    }
+
+    /// Problems with endpoint counts entered by hand: ends no played domino could show, and
+    /// ends that outnumber the unplayed dominoes that could go on them.
+    pub fn endpoint_warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        for pips in 0..=PIP_MAX_U8 {
+            let ends = usize::from(self.endpoints[usize::from(pips)]);
+            if ends == 0 {
+                continue;
+            }
+            let with_pips = |set: &DominoSet| {
+                set.as_vector()
+                    .into_iter()
+                    .filter(|&(min, max)| min == pips || max == pips)
+                    .collect::<Vec<_>>()
+            };
+            // A double lies across its line, so it never shows an end of its own.
+            let supporting = with_pips(&self.played)
+                .into_iter()
+                .filter(|&(min, max)| min != max)
+                .count();
+            let unplayed = with_pips(&self.played.inverted()).len();
+            if ends > supporting {
+                warnings.push(format!(
+                    "{ends} {pips} ends, but only {supporting} played dominoes show a {pips}."
+                ));
+            }
+            if ends > unplayed {
+                warnings.push(format!(
+                    "{ends} {pips} ends, but only {unplayed} unplayed dominoes have a {pips}."
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Whether the round has not started yet: the first double is set but not played.
+    pub fn in_opening(&self) -> bool {
+        matches!(self.double(), Some(DoubleDomino { first: true, .. })) && self.played.is_empty()
+    }
+
+    /// Begin drawing for the first double, starting with the user.
+    pub fn draw_for_double(&mut self) {
+        self.deal_boneyard();
+        self.tiles[0] = self.hand.len() as u8;
+        self.drawing_for_double = true;
+        self.turn = 0;
+        self.draws = 0;
+    }
+
+    /// Start the round: `starter` plays the first double, which must be set in `double`, and
+    /// the seat after it moves first.
+    pub fn start_round(&mut self, starter: u8) -> Result<(), String> {
+        let Some(DoubleDomino {
+            pips, first: true, ..
+        }) = self.double()
+        else {
+            return Err("Set the first double before starting the round.".to_owned());
+        };
+        if self.hand.is_empty() {
+            return Err("Enter your hand before starting the round.".to_owned());
+        }
+        match (starter, self.hand.has(pips, pips)) {
+            (0, false) => return Err(format!("You do not hold the {pips} {pips}.")),
+            (1.., true) => return Err(format!("You hold the {pips} {pips}, so you start.")),
+            _ => {}
+        }
+        if !self.drawing_for_double {
+            self.deal_boneyard();
+        }
+        self.starting = pips;
+        self.played.clear();
+        self.played.add(pips, pips);
+        self.trains.clear();
+        self.rules().start(self, starter, pips);
+        self.hand.remove(pips, pips);
+        let tiles = &mut self.tiles[usize::from(starter)];
+        *tiles = tiles.saturating_sub(1);
+        self.tiles[0] = self.hand.len() as u8;
+        self.excluded = [0; MAX_PLAYERS];
+        self.passes = 0;
+        self.draws = 0;
+        self.turn = (starter + 1) % self.players.max(1);
+        self.drawing_for_double = false;
+        Ok(())
+    }
+
+    /// `Some` once the round is over.
+    pub fn round_end(&self) -> Option<RoundEnd> {
+        self.rules().round_end(self)
+    }
+
+    /// Pips of the double the next round starts with: one lower than this round's, wrapping
+    /// around after the blank double.
+    pub fn next_double(&self) -> u8 {
+        self.starting.checked_sub(1).unwrap_or(PIP_MAX_U8)
+    }
+
+    /// Score the round given `leftover[seat]` pips in each hand and set up the next round, which starts
+    /// with the next lower double.
+    pub fn next_round(&mut self, leftover: &[u32]) {
+        let mut scores = self.scores;
+        for (score, points) in scores.iter_mut().zip(self.rules().score(self, leftover)) {
+            *score += points;
+        }
+        let starting = self.next_double();
+        *self = GameState {
+            doubles: vec![DoubleDomino {
+                pips: starting,
+                count: 0,
+                first: true,
+            }],
+            players: self.players,
+            draw_rule: self.draw_rule,
+            cover_rule: self.cover_rule,
+            variant: self.variant,
+            starting,
+            scores,
+            teams: self.teams,
+            ..GameState::default()
+        };
+        self.deal_boneyard();
+    }
+
+    /// Chance that one domino drawn from the boneyard can be played right away, assuming
+    /// every unseen domino is equally likely to be in the boneyard. Also returns the number
+    /// of playable and total unseen dominoes.
+    pub fn draw_odds(&self) -> (f32, usize, usize) {
+        let unseen = self.unseen();
+        let mut playable = self.legal_moves(&unseen);
+        playable.dedup_by_key(|mv| mv.domino());
+        let odds = if unseen.is_empty() {
+            0.0
+        } else {
+            playable.len() as f32 / unseen.len() as f32
+        };
+        (odds, playable.len(), unseen.len())
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            doubles: vec![DoubleDomino {
+                pips: 0,
+                count: 0,
+                first: true,
+            }],
+            endpoints: [0; PIP_MAX_USIZE + 1],
+            played: DominoSet { low: 0, high: 0 },
+            hand: DominoSet { low: 0, high: 0 },
+            players: 4,
+            boneyard: (DOMINO_COUNT - 4 * tiles_per_player(4)) as u8,
+            draw_rule: DrawRule::default(),
+            cover_rule: CoverRule::default(),
+            variant: Variant::default(),
+            trains: vec![],
+            turn: 0,
+            draws: 0,
+            excluded: [0; MAX_PLAYERS],
+            tiles: [tiles_per_player(4) as u8; MAX_PLAYERS],
+            passes: 0,
+            starting: 0,
+            scores: [0; MAX_PLAYERS],
+            teams: std::array::from_fn(|seat| seat as u8),
+            drawing_for_double: false,
+            board: None,
+        }
+    }
+}
+
+/// A domino played with its `end` half against a matching endpoint, leaving `open` exposed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub end: u8,
+    pub open: u8,
+}
+
+impl Move {
+    /// The domino as `(min, max)`.
+    pub fn domino(&self) -> (u8, u8) {
+        (self.end.min(self.open), self.end.max(self.open))
+    }
+
+    /// Total pips on the domino.
+    pub fn pips(&self) -> u32 {
+        u32::from(self.end) + u32::from(self.open)
+    }
+}
+
+/// Everything a seat can do on its turn.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Play(Move),
+    Draw,
+    Pass,
+}
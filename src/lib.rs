@@ -0,0 +1,19 @@
+pub mod board;
+pub mod deal;
+pub mod engine_host;
+pub mod game;
+pub mod practice;
+pub mod protocol;
+pub mod rng;
+pub mod round;
+pub mod rules;
+pub mod search;
+pub mod strategy;
+pub mod tournament;
+
+pub const PIP_MAX_U8: u8 = 12;
+pub const PIP_MAX_USIZE: usize = PIP_MAX_U8 as usize;
+/// Number of dominoes in a full set.
+pub const DOMINO_COUNT: usize = (PIP_MAX_USIZE + 1) * (PIP_MAX_USIZE + 2) / 2;
+/// Most seats at a table.
+pub const MAX_PLAYERS: usize = 8;
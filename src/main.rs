@@ -1,5 +1,18 @@
+use chicken::board::{Board, Tile};
+use chicken::engine_host::EngineHost;
+use chicken::game::{
+    Action, Block, CoverRule, DoubleDomino, DrawRule, GameState, Move, Train, Variant,
+};
+use chicken::practice::{seat_name, Practice};
+use chicken::protocol::{Command, Event, Reply};
+use chicken::rng::Rng;
+use chicken::round::RoundEnd;
+use chicken::rules::Layout;
+use chicken::search::{plan, search, Plan};
+use chicken::strategy::STRATEGY_NAMES;
+use chicken::{DOMINO_COUNT, MAX_PLAYERS, PIP_MAX_U8, PIP_MAX_USIZE};
 use eframe::egui;
-use egui::{DragValue, Frame, Image, Pos2, Rect, ScrollArea, TextBuffer, Vec2};
+use egui::{Color32, DragValue, Frame, Image, Pos2, Rect, ScrollArea, Stroke, TextBuffer, Vec2};
 
 /* TODO:
 - Better other play movement and drawing entry
@@ -7,232 +20,118 @@ use egui::{DragValue, Frame, Image, Pos2, Rect, ScrollArea, TextBuffer, Vec2};
 - Automatic testing
 */
 
-const PIP_MAX_U8: u8 = 12;
-const PIP_MAX_USIZE: usize = PIP_MAX_U8 as usize;
 const PIP_MAX_FLOAT: f32 = PIP_MAX_U8 as f32;
 
 const UNDO_SHORTCUT: egui::KeyboardShortcut =
     egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
 
-#[derive(Clone)]
-struct DominoSet {
-    /*
-    DominoSet is implemented as a bit array (2x u128) to optimize for adding,
-    removing, and testing a domino as well as copying the entire set.
-    max: min
-    low
-     0: 0 . . . . . . . . .  .  .  . . . .
-     1: 0 1 . . . . . . . .  .  .  . . . .
-     2: 0 1 2 . . . . . . .  .  .  . . . .
-     3: 0 1 2 3 . . . . . .  .  .  . . . .
-     4: 0 1 2 3 4 . . . . .  .  .  . . . .
-     5: 0 1 2 3 4 5 . . . .  .  .  . . . .
-     6: 0 1 2 3 4 5 6 . . .  .  .  . . . .
-     7: 0 1 2 3 4 5 6 7 . .  .  .  . . . .
-    high
-     8: 0 1 2 3 4 5 6 7 8 .  .  .  . . . .
-     9: 0 1 2 3 4 5 6 7 8 9  .  .  . . . .
-    10: 0 1 2 3 4 5 6 7 8 9 10  .  . . . .
-    11: 0 1 2 3 4 5 6 7 8 9 10 11  . . . .
-    12: 0 1 2 3 4 5 6 7 8 9 10 11 12 . . .
-     */
-    low: u128,
-    high: u128,
-}
-
-impl DominoSet {
-    fn clear(&mut self) {
-        self.low = 0;
-        self.high = 0;
-    }
-    fn fill(&mut self) {
-        self.low = u128::MAX;
-        self.high = u128::MAX;
-    }
-    fn has(&self, min: u8, max: u8) -> bool {
-        debug_assert!(min <= max && max <= PIP_MAX_U8);
-        // Check if corresponding bit is set.
-        if max < 8 {
-            (self.low & (1 << (max * 16 + min))) >> (max * 16 + min) != 0
-        } else {
-            (self.high & (1 << ((max - 8) * 16 + min))) >> ((max - 8) * 16 + min) != 0
-        }
-    }
-    fn add(&mut self, min: u8, max: u8) {
-        // Set corresponding bit to 1.
-        debug_assert!(min <= max && max <= PIP_MAX_U8);
-        if max < 8 {
-            self.low |= 1 << (max * 16 + min);
-        } else {
-            self.high |= 1 << ((max - 8) * 16 + min);
-        }
-    }
-    fn remove(&mut self, min: u8, max: u8) {
-        debug_assert!(min <= max && max <= PIP_MAX_U8);
-        // Set corresponding bit to 0.
-        if max < 8 {
-            self.low &= !(1 << (max * 16 + min));
-        } else {
-            self.high &= !(1 << ((max - 8) * 16 + min));
-        }
-    }
-    fn toggle(&mut self, min: u8, max: u8) {
-        debug_assert!(min <= max && max <= PIP_MAX_U8);
-        // Set corresponding bit to 0.
-        if max < 8 {
-            self.low ^= 1 << (max * 16 + min);
-        } else {
-            self.high ^= 1 << ((max - 8) * 16 + min);
-        }
-    }
-    fn inverted(&self) -> Self {
-        DominoSet {
-            low: !self.low,
-            high: !self.high,
-        }
-    }
-    fn as_vector(&self) -> Vec<(u8, u8)> {
-        // Convert to vector.
-        let mut dominoes: Vec<(u8, u8)> = vec![];
-        let mut bits = self.low;
-        for max in 0..8 {
-            for min in 0..(max + 1) {
-                if bits & 1 != 0 {
-                    dominoes.push((min, max));
-                }
-                bits >>= 1;
-            }
-            bits >>= 15 - max;
-        }
-        bits = self.high;
-        for max in 8..(PIP_MAX_U8 + 1) {
-            for min in 0..(max + 1) {
-                if bits & 1 != 0 {
-                    dominoes.push((min, max));
-                }
-                bits >>= 1;
-            }
-            bits >>= 15 - max;
-        }
-        dominoes
-    }
+fn domino_image<'a>(min: u8, max: u8) -> Image<'a> {
+    Image::new(egui::include_image!("../generateDominoes/set.png")).uv(Rect::from_min_size(
+        Pos2::new(min as f32 / 13.0, max as f32 / 13.0),
+        Vec2::new(1.0 / 13.0, 1.0 / 13.0),
+    ))
 }
-
-#[derive(Default, Clone)]
-struct DoubleDomino {
-    pips: u8,
-    count: u8,
-    first: bool,
+fn pips_image<'a>(pips: u8) -> Image<'a> {
+    Image::new(egui::include_image!("../generateDominoes/pips.png")).uv(Rect::from_min_size(
+        Pos2::new(pips as f32 / 13.0, 0.0),
+        Vec2::new(1.0 / 13.0, 1.0 / 13.0),
+    ))
 }
 
-impl DoubleDomino {
-    fn max_count(&self) -> u8 {
-        if self.first {
-            4
-        } else {
-            3
-        }
+/// `domino` played with its `pips` half against an end, if it has one.
+fn move_onto((min, max): (u8, u8), pips: u8) -> Option<Move> {
+    if min == pips {
+        Some(Move {
+            end: min,
+            open: max,
+        })
+    } else if max == pips {
+        Some(Move {
+            end: max,
+            open: min,
+        })
+    } else {
+        None
     }
 }
 
-#[derive(Clone)]
-struct GameState {
-    /// `Some(DoubleDomino)` if a double (other than the first) is in play. Set to None when count reaches 4 (first) or 3 (not first).
-    double: Option<DoubleDomino>,
-    /// Count of endpoints (i.e., where dominoes can be played) with [index] pips
-    endpoints: [u8; PIP_MAX_USIZE + 1],
-    /// Dominoes in play
-    played: DominoSet,
-    /// Dominoes in the user's hand
-    hand: DominoSet,
+/// Where a tile sits on the board canvas, in domino widths from the first double.
+#[derive(Clone, Copy)]
+struct Placement {
+    center: Vec2,
+    /// Unit vector from the parent towards the open end.
+    direction: Vec2,
 }
 
-impl GameState {
-    /// Play a domino. Places the `min` end on the endpoint if `min_matches` is `true`.
-    /// Did nothing if `Err` is returned.
-    fn play(&mut self, min: u8, max: u8, min_matches: bool) -> Result<(), String> {
-        #[cfg(debug_assertions)]
-        if self.played.has(min, max) {
-            return Err(format!("Domino {min} {max} has already been played."));
+impl Placement {
+    /// Half the tile's length along `direction`. Doubles lie across their line; the first
+    /// double stands upright.
+    fn reach(tile: &Tile, direction: Vec2) -> f32 {
+        match (tile.is_double(), tile.parent) {
+            (true, None) => 0.5 * direction.x.abs() + direction.y.abs(),
+            (true, Some(_)) => 0.5,
+            (false, _) => 1.0,
         }
+    }
 
-        // Rotate domino.
-        let (previous_endpoint, next_endpoint) = if min_matches {
-            (min as usize, max as usize)
+    /// Where the next domino goes: the open half, or the whole tile for a double.
+    fn open_spot(tile: &Tile, placement: &Placement) -> Vec2 {
+        if tile.is_double() {
+            placement.center
         } else {
-            (max as usize, min as usize)
-        };
-
-        // Play on a double domino.
-        if let Some(double_domino) = &mut self.double {
-            debug_assert!(double_domino.count < 4);
-            if usize::from(double_domino.pips) != previous_endpoint {
-                return Err(format!(
-                    "Invalid Move: play a {} on the double domino.",
-                    double_domino.pips
-                ));
-            }
-            self.endpoints[next_endpoint] += 1;
-            double_domino.count += 1;
-            if double_domino.count >= double_domino.max_count() {
-                self.double = None;
-            }
-            self.played.add(min, max);
-            return Ok(());
+            placement.center + placement.direction * 0.5
         }
-
-        // Play a double domino.
-        if min == max {
-            if self.endpoints[previous_endpoint] == 0 {
-                return Err("Invalid Move: play on an end.".to_owned());
-            }
-            self.endpoints[previous_endpoint] -= 1;
-            self.double = Some(DoubleDomino {
-                pips: min,
-                count: 0,
-                first: false,
-            });
-            self.played.add(min, max);
-            return Ok(());
-        }
-
-        // Play a non-double domino on another non-double domino.
-        if self.endpoints[previous_endpoint] == 0 {
-            return Err("Invalid Move: play on an end.".to_owned());
-        }
-        self.endpoints[previous_endpoint] -= 1;
-        self.endpoints[next_endpoint] += 1;
-        self.played.add(min, max);
-        return Ok(());
     }
-}
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self {
-            double: Some(DoubleDomino {
-                pips: 0,
-                count: 0,
-                first: true,
-            }),
-            endpoints: [0; PIP_MAX_USIZE + 1],
-            played: DominoSet { low: 0, high: 0 },
-            hand: DominoSet { low: 0, high: 0 },
+    /// Rotation of the upright `domino_image` sprite.
+    fn angle(tile: &Tile, placement: &Placement) -> f32 {
+        let heading = placement.direction.angle();
+        match (tile.is_double(), tile.parent) {
+            (true, None) => 0.0,
+            (true, Some(_)) => heading,
+            // The sprite shows `min` on top; turn it so `end` faces the parent.
+            (false, _) if tile.end() == tile.min => heading - std::f32::consts::FRAC_PI_2,
+            (false, _) => heading + std::f32::consts::FRAC_PI_2,
         }
     }
 }
 
-fn domino_image<'a>(min: u8, max: u8) -> Image<'a> {
-    Image::new(egui::include_image!("../generateDominoes/set.png")).uv(Rect::from_min_size(
-        Pos2::new(min as f32 / 13.0, max as f32 / 13.0),
-        Vec2::new(1.0 / 13.0, 1.0 / 13.0),
-    ))
-}
-fn pips_image<'a>(pips: u8) -> Image<'a> {
-    Image::new(egui::include_image!("../generateDominoes/pips.png")).uv(Rect::from_min_size(
-        Pos2::new(pips as f32 / 13.0, 0.0),
-        Vec2::new(1.0 / 13.0, 1.0 / 13.0),
-    ))
+/// Lay the board out as it sits on the table: four lines out of the first double, straight
+/// lines, and three toes spreading out of every other double.
+fn board_layout(board: &Board) -> Vec<Placement> {
+    const SPOKES: [Vec2; 4] = [Vec2::X, Vec2::new(-1.0, 0.0), Vec2::Y, Vec2::new(0.0, -1.0)];
+    const TOES: [f32; 3] = [
+        -std::f32::consts::FRAC_PI_4,
+        0.0,
+        std::f32::consts::FRAC_PI_4,
+    ];
+    let mut layout: Vec<Placement> = Vec::with_capacity(board.len());
+    let mut children = vec![0; board.len()];
+    for tile in &board.tiles {
+        let Some(parent) = tile.parent else {
+            layout.push(Placement {
+                center: Vec2::ZERO,
+                direction: Vec2::Y,
+            });
+            continue;
+        };
+        let above = &board.tiles[parent];
+        let base = layout[parent];
+        let direction = match (above.is_double(), above.parent) {
+            (true, None) => SPOKES[children[parent] % 4],
+            // Doubles after a spinner lie across their line.
+            (true, Some(_)) if board.spinner => base.direction,
+            (true, Some(_)) => Vec2::angled(base.direction.angle() + TOES[children[parent] % 3]),
+            (false, _) => base.direction,
+        };
+        children[parent] += 1;
+        let gap = Placement::reach(above, direction) + Placement::reach(tile, direction);
+        layout.push(Placement {
+            center: base.center + direction * gap,
+            direction,
+        });
+    }
+    layout
 }
 
 struct MainWindow {
@@ -241,11 +140,74 @@ struct MainWindow {
     text_edit: String,
     info: String,
     painter: egui::Painter,
+    /// Command line used to launch an external engine.
+    engine_command: String,
+    engine: Option<EngineHost>,
+    /// Recent `info` lines from the engine.
+    engine_output: Vec<String>,
+    engine_suggestion: Option<Action>,
+    /// Game against the computer. While active, `game_state` mirrors what seat 0 can see.
+    practice: Option<Practice>,
+    /// Assistant state to restore when the practice game is closed.
+    practice_saved_state: Option<GameState>,
+    practice_bots: usize,
+    /// Index into `STRATEGY_NAMES`.
+    practice_strategy: usize,
+    /// Time at which the next bot may act.
+    practice_next_step: f64,
+    /// Seed for the next practice game. Random if empty.
+    practice_seed: String,
+    /// File practice games are saved to and loaded from.
+    practice_path: String,
+    /// Pips left in each opponent's hand, entered when a round ends.
+    leftover: [u32; MAX_PLAYERS],
+    /// Show the board canvas instead of the played grid.
+    board_view: bool,
+    /// Pixels per domino width on the board canvas.
+    board_zoom: f32,
+    /// Offset of the first double from the center of the board canvas.
+    board_pan: Vec2,
+    /// Keep the whole board in view until the user zooms or pans.
+    board_fit: bool,
+    /// Toggle played dominoes in the grid without recording a play.
+    raw_edit: bool,
+    /// Domino clicked in the grid, and the seat that played it, waiting for its end.
+    grid_play: Option<(u8, u8, u8)>,
+    /// Open end picked on the board canvas for the next domino played from the hand.
+    selected_end: Option<usize>,
+    /// Plan for the hand, and the hand, played dominoes and ends it was made for.
+    plan: Option<(PlanKey, Plan)>,
+}
+
+/// Everything the plan for the hand depends on.
+#[derive(PartialEq)]
+struct PlanKey {
+    hand: (u128, u128),
+    played: (u128, u128),
+    endpoints: [u8; PIP_MAX_USIZE + 1],
+    doubles: Vec<DoubleDomino>,
+    trains: Vec<Train>,
+    cover_rule: CoverRule,
+    variant: Variant,
+}
+
+impl PlanKey {
+    fn new(state: &GameState) -> Self {
+        Self {
+            hand: (state.hand.low, state.hand.high),
+            played: (state.played.low, state.played.high),
+            endpoints: state.endpoints,
+            doubles: state.doubles.clone(),
+            trains: state.trains.clone(),
+            cover_rule: state.cover_rule,
+            variant: state.variant,
+        }
+    }
 }
 
 impl MainWindow {
     fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let self_ = Self {
+        Self {
             game_state: GameState::default(),
             stack: Vec::new(),
             text_edit: String::default(),
@@ -254,28 +216,183 @@ impl MainWindow {
                 egui::Order::Foreground,
                 egui::Id::new("overlay"),
             )),
-        };
-        self_
+            engine_command: String::default(),
+            engine: None,
+            engine_output: Vec::new(),
+            engine_suggestion: None,
+            practice: None,
+            practice_saved_state: None,
+            practice_bots: 3,
+            practice_strategy: 1,
+            practice_next_step: 0.0,
+            practice_seed: String::default(),
+            practice_path: "practice.txt".to_owned(),
+            leftover: [0; MAX_PLAYERS],
+            board_view: false,
+            board_zoom: 30.0,
+            board_pan: Vec2::ZERO,
+            board_fit: true,
+            selected_end: None,
+            raw_edit: false,
+            grid_play: None,
+            plan: None,
+        }
     }
 
     fn push_stack(&mut self) {
         self.stack.push(self.game_state.clone());
     }
 
+    /// Each team's name and combined `values`, such as scores or pips left. Empty unless
+    /// seats play as partners.
+    fn team_totals(&self, values: &[u32; MAX_PLAYERS]) -> Vec<(String, u32)> {
+        if !self.game_state.has_teams() {
+            return vec![];
+        }
+        let mut totals = vec![];
+        for seat in 0..self.game_state.players {
+            let team = self.game_state.team(seat);
+            if team[0] != seat {
+                continue;
+            }
+            let names: Vec<String> = team.iter().map(|&seat| seat_name(seat.into())).collect();
+            let total = team.iter().map(|&seat| values[usize::from(seat)]).sum();
+            totals.push((names.join(" and "), total));
+        }
+        totals
+    }
+
+    /// The table as laid out, with zoom, pan and open ends to click.
+    fn draw_board(&mut self, ui: &mut egui::Ui) {
+        let Some(board) = self.game_state.board.clone() else {
+            ui.label(if self.game_state.rules().layout() == Layout::Trains {
+                "The board view shows Chickenfoot layouts. See Trains."
+            } else {
+                "Layout unknown since the table was edited by hand."
+            });
+            return;
+        };
+        let (response, painter) =
+            ui.allocate_painter(ui.available_size(), egui::Sense::click_and_drag());
+        let layout = board_layout(&board);
+
+        if response.dragged() {
+            self.board_pan += response.drag_delta();
+            self.board_fit = false;
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                // Zoom around the pointer.
+                let factor = (scroll / 200.0).exp();
+                let offset = pointer - response.rect.center();
+                self.board_pan = offset + (self.board_pan - offset) * factor;
+                self.board_zoom = (self.board_zoom * factor).clamp(5.0, 200.0);
+                self.board_fit = false;
+            }
+        }
+        if self.board_fit {
+            let mut bounds = Rect::NOTHING;
+            for placement in &layout {
+                bounds.extend_with(placement.center.to_pos2());
+            }
+            let bounds = bounds.expand(1.5);
+            self.board_zoom = (response.rect.width() / bounds.width())
+                .min(response.rect.height() / bounds.height())
+                .clamp(5.0, 200.0);
+            self.board_pan = -bounds.center().to_vec2() * self.board_zoom;
+        }
+        let (center, zoom) = (response.rect.center() + self.board_pan, self.board_zoom);
+        let to_screen = |unit: Vec2| center + unit * zoom;
+
+        for (tile, placement) in board.tiles.iter().zip(&layout) {
+            let rect = Rect::from_center_size(
+                to_screen(placement.center),
+                Vec2::new(self.board_zoom, 2.0 * self.board_zoom),
+            );
+            domino_image(tile.min, tile.max)
+                .rotate(Placement::angle(tile, placement), Vec2::splat(0.5))
+                .paint_at(ui, rect);
+        }
+
+        let open_ends = board.open_ends(self.game_state.cover_rule);
+        for &index in &open_ends {
+            let color = if self.selected_end == Some(index) {
+                Color32::GREEN
+            } else {
+                Color32::YELLOW
+            };
+            painter.circle_stroke(
+                to_screen(Placement::open_spot(&board.tiles[index], &layout[index])),
+                0.6 * self.board_zoom,
+                Stroke::new(2.0, color),
+            );
+        }
+        let end_at = |pointer: Pos2| {
+            open_ends.iter().copied().find(|&index| {
+                let spot = to_screen(Placement::open_spot(&board.tiles[index], &layout[index]));
+                spot.distance(pointer) < 0.6 * zoom
+            })
+        };
+        let hovered = ui
+            .ctx()
+            .pointer_hover_pos()
+            .filter(|&pointer| response.rect.contains(pointer))
+            .and_then(end_at);
+        if let Some(index) = hovered {
+            // Drop a domino from the hand on an open end.
+            let spot = to_screen(Placement::open_spot(&board.tiles[index], &layout[index]));
+            let rect = Rect::from_center_size(spot, Vec2::splat(1.2 * self.board_zoom));
+            let pips = board.tiles[index].open;
+            self.drop_target(ui, &response, rect, |domino| {
+                move_onto(domino, pips).map(|mv| (mv, Some(index)))
+            });
+        }
+        if response.clicked() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let picked = end_at(pointer);
+                self.selected_end = if picked == self.selected_end {
+                    None
+                } else {
+                    picked
+                };
+                if let Some(index) = self.selected_end {
+                    self.info = format!(
+                        "Picked a {} end: click a domino in your hand to play it there.",
+                        board.tiles[index].open
+                    );
+                }
+            }
+        }
+    }
+
     fn draw_played_dominoes(&mut self, ui: &mut egui::Ui) {
         ui.heading("Played Dominoes");
         let re = ui
             .image(egui::include_image!("../generateDominoes/set.png"))
             .interact(egui::Sense::click());
-        if re.clicked() {
+        if re.clicked() && self.practice.is_none() {
             if let Some(Pos2 { x, y }) = re.interact_pointer_pos() {
                 let min =
                     ((x - re.rect.left()) / re.rect.width() * 13.0).clamp(0.0, PIP_MAX_FLOAT) as u8;
                 let max =
                     ((y - re.rect.top()) / re.rect.height() * 13.0).clamp(0.0, PIP_MAX_FLOAT) as u8;
-                if min <= max && max <= PIP_MAX_U8 {
+                if min > max || max > PIP_MAX_U8 {
+                    // Below the diagonal: not a domino.
+                } else if self.raw_edit {
                     self.push_stack();
                     self.game_state.played.toggle(min, max);
+                    self.game_state.board = None;
+                } else if self.game_state.played.has(min, max) {
+                    self.info
+                        .replace_with("Undo the play, or use raw edit to take a domino back.");
+                } else {
+                    let seat = if self.game_state.hand.has(min, max) || self.game_state.turn == 0 {
+                        0
+                    } else {
+                        self.game_state.turn
+                    };
+                    self.grid_play = Some((min, max, seat));
                 }
             }
         }
@@ -304,26 +421,150 @@ impl MainWindow {
         }
     }
 
+    /// Ask which end a domino clicked in the grid went on, then record the play.
+    fn draw_grid_play(&mut self, ui: &mut egui::Ui) {
+        let Some((min, max, mut seat)) = self.grid_play else {
+            return;
+        };
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame
+            .content_ui
+            .label(format!("Record the {min} {max}. Played by:"));
+        frame.content_ui.horizontal(|ui| {
+            for player in 0..self.game_state.players {
+                ui.radio_value(&mut seat, player, seat_name(usize::from(player)));
+            }
+        });
+        self.grid_play = Some((min, max, seat));
+        let mut ends = vec![];
+        if !self.game_state.in_opening() {
+            let double = self.game_state.double();
+            ends.extend(double.map(|double| double.pips));
+            if double.is_none() || self.game_state.cover_rule == CoverRule::InOrder {
+                ends.extend(
+                    [min, max]
+                        .into_iter()
+                        .filter(|&pips| self.game_state.endpoints[usize::from(pips)] > 0),
+                );
+            }
+        }
+        ends.sort_unstable();
+        ends.dedup();
+        let mut chosen = None;
+        frame.content_ui.horizontal(|ui| {
+            for &pips in ends.iter().filter(|&&pips| pips == min || pips == max) {
+                if ui.button(format!("On a {pips} end")).clicked() {
+                    chosen = move_onto((min, max), pips);
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                self.grid_play = None;
+            }
+        });
+        if ends.iter().all(|&pips| pips != min && pips != max) {
+            frame
+                .content_ui
+                .label("No open end matches it. Use raw edit to add it anyway.");
+        }
+        frame.end(ui);
+
+        if let Some(mv) = chosen {
+            let state_copy = self.game_state.clone();
+            if seat != self.game_state.turn {
+                self.game_state.turn = seat;
+                self.game_state.draws = 0;
+            }
+            if let Err(text) = self
+                .end_for(mv)
+                .and_then(|at| self.game_state.record_play_at(mv, at))
+            {
+                self.game_state = state_copy;
+                self.info = text;
+            } else {
+                self.stack.push(state_copy);
+                self.grid_play = None;
+                self.selected_end = None;
+                self.info.clear();
+                self.notify_engine(seat, Event::Play(mv));
+            }
+        }
+    }
+
+    /// Summary shown once the round is over, with a button to start the next one.
+    fn draw_round_end(&mut self, ui: &mut egui::Ui) {
+        let Some(end) = self.game_state.round_end() else {
+            return;
+        };
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Round Over");
+        frame.content_ui.strong(match end {
+            RoundEnd::Out(seat) => format!("{} went out.", seat_name(seat)),
+            RoundEnd::Blocked => "The game is blocked: nobody can play.".to_owned(),
+        });
+        self.leftover[0] = self.game_state.hand.pips();
+        frame
+            .content_ui
+            .label(format!("You: {} pips left", self.leftover[0]));
+        for seat in 1..usize::from(self.game_state.players) {
+            if end == RoundEnd::Out(seat) {
+                self.leftover[seat] = 0;
+                frame
+                    .content_ui
+                    .label(format!("{}: 0 pips left", seat_name(seat)));
+                continue;
+            }
+            frame.content_ui.horizontal(|ui| {
+                ui.label(format!("{}:", seat_name(seat)));
+                ui.add(DragValue::new(&mut self.leftover[seat]));
+                ui.label(format!(
+                    "pips left in {} dominoes",
+                    self.game_state.tiles[seat]
+                ));
+            });
+        }
+        for (team, pips) in self.team_totals(&self.leftover) {
+            frame
+                .content_ui
+                .strong(format!("{team}: {pips} pips left together"));
+        }
+        let next = self.game_state.next_double();
+        if frame
+            .content_ui
+            .button(format!("Start Next Round ({next} {next} double)"))
+            .clicked()
+        {
+            self.push_stack();
+            let players = usize::from(self.game_state.players);
+            self.game_state.next_round(&self.leftover[..players]);
+            self.leftover = [0; MAX_PLAYERS];
+            self.info
+                .replace_with("Enter your new hand, then start the game.");
+            if let Some(engine) = &mut self.engine {
+                if let Err(error) = engine.send(&Command::NewGame) {
+                    self.info = format!("Engine error: {error}");
+                }
+            }
+        }
+        frame.end(ui);
+    }
+
     fn draw_double(&mut self, ui: &mut egui::Ui) {
         let mut frame = Frame::group(ui.style()).begin(ui);
         frame.content_ui.heading("Double Domino");
-        let mut active = self.game_state.double.is_some();
+        let mut active = self.game_state.double().is_some();
         if frame.content_ui.checkbox(&mut active, "Active").changed() {
             self.push_stack();
-            self.game_state.double = if active {
-                Some(DoubleDomino::default())
+            self.game_state.doubles = if active {
+                vec![DoubleDomino::default()]
             } else {
-                None
+                vec![]
             };
+            self.game_state.board = None;
         }
+        let mut edited = false;
         frame.content_ui.horizontal(|ui| {
-            let max_count = self
-                .game_state
-                .double
-                .as_ref()
-                .and_then(|d| Some(d.max_count()));
-            let state_copy = self.game_state.clone();
-            if let Some(DoubleDomino { pips, count, first }) = &mut self.game_state.double {
+            let max_count = self.game_state.double().map(|d| d.max_count());
+            if let Some(DoubleDomino { pips, count, first }) = self.game_state.doubles.first_mut() {
                 let max_count = max_count.unwrap();
                 ui.horizontal(|ui| {
                     let label = if *first {
@@ -331,100 +572,516 @@ impl MainWindow {
                     } else {
                         "Chickenfoot"
                     };
-                    ui.toggle_value(first, label);
+                    edited |= ui.toggle_value(first, label).changed();
                     ui.label("Pips:");
-                    ui.add(DragValue::new(pips).speed(0.05).range(0..=PIP_MAX_U8));
+                    edited |= ui
+                        .add(DragValue::new(pips).speed(0.05).range(0..=PIP_MAX_U8))
+                        .changed();
                     ui.label(format!("Played / {max_count}:"));
-                    ui.add(DragValue::new(count).speed(0.05).range(0..=(max_count - 1)));
+                    edited |= ui
+                        .add(DragValue::new(count).speed(0.05).range(0..=(max_count - 1)))
+                        .changed();
                 });
-                if *first && ui.button("Start Game").clicked() {
-                    self.stack.push(state_copy);
-                    self.game_state.played.clear();
-                    self.game_state.played.add(*pips, *pips);
-                }
             }
         });
-        if let Some(DoubleDomino { pips, .. }) = self.game_state.double {
+        if edited {
+            self.game_state.board = None;
+        }
+        let waiting: Vec<String> = self
+            .game_state
+            .doubles
+            .iter()
+            .skip(1)
+            .map(|double| format!("{} {} ({} / 3)", double.pips, double.pips, double.count))
+            .collect();
+        if !waiting.is_empty() {
+            frame
+                .content_ui
+                .label(format!("Then cover: {}", waiting.join(", ")));
+        }
+        if let Some(DoubleDomino { pips, .. }) = self.game_state.double() {
             frame
                 .content_ui
                 .add_sized([60.0, 120.0], domino_image(pips, pips));
         }
+        if self.game_state.in_opening() {
+            self.draw_opening(&mut frame.content_ui);
+        }
         frame.end(ui);
     }
 
-    fn draw_endpoints(&mut self, ui: &mut egui::Ui) {
+    /// Work out who plays the first double: the user, an opponent, or whoever finds it while
+    /// every seat draws in turn.
+    fn draw_opening(&mut self, ui: &mut egui::Ui) {
+        let Some(DoubleDomino { pips, .. }) = self.game_state.double() else {
+            return;
+        };
+        let state_copy = self.game_state.clone();
+        let mut starter = None;
+        if self.game_state.hand.has(pips, pips) {
+            ui.label(format!("You hold the {pips} {pips}, so you start."));
+            if ui.button(format!("Play the {pips} {pips}")).clicked() {
+                starter = Some(0);
+            }
+        } else if !self.game_state.drawing_for_double {
+            ui.label(format!(
+                "Enter your hand with Draw, then pick who holds the {pips} {pips}."
+            ));
+            ui.horizontal(|ui| {
+                for seat in 1..self.game_state.players {
+                    if ui.button(seat_name(usize::from(seat))).clicked() {
+                        starter = Some(seat);
+                    }
+                }
+                if ui
+                    .button("Nobody")
+                    .on_hover_text("Every seat draws in turn until someone finds it.")
+                    .clicked()
+                {
+                    self.push_stack();
+                    self.game_state.draw_for_double();
+                }
+            });
+        } else if self.game_state.turn == 0 {
+            ui.label(format!(
+                "Your turn to draw for the {pips} {pips}: enter the domino you drew with Draw."
+            ));
+        } else {
+            let seat = self.game_state.turn;
+            ui.label(format!("Seat {seat} draws for the {pips} {pips}."));
+            ui.horizontal(|ui| {
+                if ui.button("Found it").clicked() {
+                    self.game_state.record_draw();
+                    starter = Some(seat);
+                }
+                if ui.button("Drew another").clicked() {
+                    self.push_stack();
+                    self.game_state.record_draw();
+                    self.game_state.end_turn();
+                    self.notify_engine(seat, Event::Draw(None));
+                }
+            });
+        }
+        if let Some(seat) = starter {
+            match self.game_state.start_round(seat) {
+                Ok(()) => {
+                    self.stack.push(state_copy);
+                    self.info.clear();
+                }
+                Err(text) => {
+                    self.game_state = state_copy;
+                    self.info = text;
+                }
+            }
+        }
+    }
+
+    /// Mexican Train: each seat's train and the Mexican train, with markers.
+    fn draw_trains(&mut self, ui: &mut egui::Ui) {
         let mut frame = Frame::group(ui.style()).begin(ui);
-        frame.content_ui.heading("Endpoints");
-        frame.content_ui.horizontal(|ui| {
-            ui.label("Set pips and count:");
-            let re = ui.text_edit_singleline(&mut self.text_edit);
-            if re.lost_focus() && re.ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-                let parts: Vec<&str> = self.text_edit.split(" ").collect();
-                if parts.len() == 2 {
-                    match (parts[0].parse::<u8>(), parts[1].parse::<u8>()) {
-                        (Ok(pips), Ok(count))if pips <= PIP_MAX_U8 && count <= PIP_MAX_U8 => {
-                            self.push_stack();
-                            self.game_state.endpoints[pips as usize] = count;
-                            self.text_edit.clear();
-                            self.info.clear();
-                        }
-                        _ => {
-                            self.info.replace_with(
-                                "Set endpoints command must be entered as two numbers 0-12 seperated by a space.",
+        frame.content_ui.heading("Trains");
+        if self.game_state.trains.is_empty() {
+            frame
+                .content_ui
+                .label("The trains start once the hub double is played.");
+        }
+        let editable = self.practice.is_none();
+        for index in 0..self.game_state.trains.len() {
+            let train = self.game_state.trains[index];
+            frame.content_ui.horizontal(|ui| {
+                let target = ui
+                    .add_sized([40.0, 40.0], pips_image(train.end))
+                    .interact(egui::Sense::click());
+                if target.clicked() {
+                    self.selected_end = if self.selected_end == Some(index) {
+                        None
+                    } else {
+                        Some(index)
+                    };
+                }
+                if self.selected_end == Some(index) {
+                    ui.painter()
+                        .rect_stroke(target.rect, 4.0, Stroke::new(2.0, Color32::GREEN));
+                }
+                let end = train.end;
+                self.drop_target(ui, &target, target.rect, |domino| {
+                    move_onto(domino, end).map(|mv| (mv, Some(index)))
+                });
+                ui.label(match index {
+                    0 => "Your train".to_owned(),
+                    _ if index == self.game_state.mexican_train() => "Mexican train".to_owned(),
+                    _ => format!("Seat {index}'s train"),
+                });
+                let mut marker = train.marker;
+                if ui
+                    .add_enabled(editable, egui::Checkbox::new(&mut marker, "Marker"))
+                    .changed()
+                {
+                    self.push_stack();
+                    self.game_state.trains[index].marker = marker;
+                }
+                if train.double {
+                    ui.strong(format!("Cover the {end} {end} double."));
+                } else if self.game_state.train_open(0, index) {
+                    ui.label("Open to you");
+                }
+            });
+        }
+        frame.end(ui);
+    }
+
+    /// Per pip value, where its dominoes are and how many ends show it.
+    fn draw_suits(&mut self, ui: &mut egui::Ui) {
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Suits");
+        egui::Grid::new("suits")
+            .striped(true)
+            .show(&mut frame.content_ui, |ui| {
+                for header in ["Pips", "Played", "In hand", "Unseen", "Open ends", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for pips in 0..=PIP_MAX_U8 {
+                    let suit = self.game_state.suit(pips);
+                    ui.label(pips.to_string());
+                    ui.label(suit.played.to_string());
+                    ui.label(suit.hand.to_string());
+                    ui.label(suit.unseen.to_string());
+                    ui.label(suit.ends.to_string());
+                    if suit.dead() {
+                        ui.colored_label(ui.visuals().warn_fg_color, "Dead")
+                            .on_hover_text(
+                                "Every domino left is in your hand, so nobody else can play on these ends.",
                             );
-                        }
+                    } else {
+                        ui.label("");
                     }
-                } else {
-                    self.info.replace_with(
-                        "Set endpoints command must be entered as two numbers 0-12 seperated by a space.",
-                    );
+                    ui.end_row();
                 }
-                re.request_focus();
-            }
-        });
+            });
+        frame.end(ui);
+    }
+
+    fn draw_endpoints(&mut self, ui: &mut egui::Ui) {
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Endpoints");
+        let editable = self.practice.is_none();
+        if editable {
+            frame
+                .content_ui
+                .label("+ and − add and remove ends. Click an end to remove it.");
+        }
         frame.content_ui.horizontal_wrapped(|ui| {
-            let mut pips: u8 = 0;
-            for count in self.game_state.endpoints {
+            if let Some(DoubleDomino { pips, .. }) = self.game_state.double() {
+                // Everything goes on the double until it is covered.
+                let target = ui.add_sized([60.0, 120.0], domino_image(pips, pips));
+                self.drop_target(ui, &target, target.rect, |domino| {
+                    move_onto(domino, pips).map(|mv| (mv, None))
+                });
+            }
+            for (pips, count) in (0..).zip(self.game_state.endpoints) {
                 ui.vertical(|ui| {
+                    let mut change = 0;
+                    if editable {
+                        ui.horizontal(|ui| {
+                            ui.label(pips.to_string());
+                            if ui.small_button("+").clicked() {
+                                change = 1;
+                            }
+                            if ui
+                                .add_enabled(count > 0, egui::Button::new("−").small())
+                                .clicked()
+                            {
+                                change = -1;
+                            }
+                        });
+                    }
                     for _ in 0..count {
-                        ui.add_sized([60.0, 60.0], pips_image(pips));
+                        let target = ui
+                            .add_sized([60.0, 60.0], pips_image(pips))
+                            .interact(egui::Sense::click());
+                        if editable && target.clicked() {
+                            change = -1;
+                        }
+                        self.drop_target(ui, &target, target.rect, |domino| {
+                            move_onto(domino, pips).map(|mv| (mv, None))
+                        });
+                    }
+                    if change != 0 {
+                        self.push_stack();
+                        let endpoints = &mut self.game_state.endpoints[usize::from(pips)];
+                        *endpoints = endpoints.saturating_add_signed(change);
+                        self.game_state.board = None;
                     }
                 });
-                pips += 1;
             }
         });
+        if editable && self.game_state.board.is_none() {
+            // Counts derived from the board are sound; check the ones edited by hand.
+            for warning in self.game_state.endpoint_warnings() {
+                frame
+                    .content_ui
+                    .colored_label(frame.content_ui.visuals().warn_fg_color, warning);
+            }
+        }
+        if let Some(sum) = self.game_state.rules().end_sum(&self.game_state) {
+            frame
+                .content_ui
+                .strong(format!("The ends add up to {sum}."));
+            match self.game_state.best_scoring_play() {
+                Some((mv, at, points)) => {
+                    let (min, max) = mv.domino();
+                    let text = format!(
+                        "Best play: the {min} {max} on a {} end for {points} points.",
+                        mv.end
+                    );
+                    frame.content_ui.horizontal(|ui| {
+                        ui.label(text);
+                        if ui.button("Play It").clicked() {
+                            self.play_from_hand(mv, at);
+                        }
+                    });
+                }
+                None => {
+                    frame.content_ui.label("Nothing in your hand plays.");
+                }
+            }
+        }
+        match &self.game_state.board {
+            Some(board) => {
+                // Each open end and the line leading to it.
+                for index in board.open_ends(self.game_state.cover_rule) {
+                    let line: Vec<String> = board
+                        .path(index)
+                        .into_iter()
+                        .map(|tile| {
+                            format!("{}-{}", board.tiles[tile].end(), board.tiles[tile].open)
+                        })
+                        .collect();
+                    frame.content_ui.label(format!(
+                        "{} end: {}",
+                        board.tiles[index].open,
+                        line.join(" ")
+                    ));
+                }
+            }
+            None if !self.game_state.played.is_empty() => {
+                frame
+                    .content_ui
+                    .label("Layout unknown since the table was edited by hand.");
+            }
+            None => {}
+        }
         frame.end(ui);
     }
 
+    /// Whether the user could play `mv` on tile `at` right now. See `play_from_hand`.
+    fn check_play(&self, mv: Move, at: Option<usize>) -> Result<(), String> {
+        if self
+            .practice
+            .as_ref()
+            .is_some_and(|practice| !practice.human_to_move())
+        {
+            return Err("Wait for your turn.".to_owned());
+        }
+        self.game_state.clone().play_at(mv, at)
+    }
+
+    /// Play `mv` from the user's hand on tile `at` of the board, or on the first matching
+    /// end if `None`.
+    fn play_from_hand(&mut self, mv: Move, at: Option<usize>) {
+        if let Some(practice) = &mut self.practice {
+            // Practice game: the round enforces turns and rules.
+            if !practice.human_to_move() {
+                self.info.replace_with("Wait for your turn.");
+            } else if let Err(text) = practice.apply_at(Action::Play(mv), at) {
+                self.info = text;
+            } else {
+                self.selected_end = None;
+                self.info.clear();
+            }
+            return;
+        }
+        let state_copy = self.game_state.clone();
+        self.game_state.turn = 0;
+        if let Err(text) = self.game_state.record_play_at(mv, at) {
+            // Report invalid play.
+            self.game_state = state_copy;
+            self.info = text;
+        } else {
+            // Play successful: the domino left the player's hand.
+            self.stack.push(state_copy);
+            self.selected_end = None;
+            self.info.clear();
+            self.notify_engine(0, Event::Play(mv));
+        }
+    }
+
+    /// End another seat played `mv` on: the one picked on the board if it fits, else the
+    /// only end it fits. `Err` if it fits several and none is picked.
+    fn end_for(&self, mv: Move) -> Result<Option<usize>, String> {
+        let state = &self.game_state;
+        if state
+            .board
+            .as_ref()
+            .is_some_and(|board| board.len() != state.played.len())
+        {
+            // The board is out of date, so plays go by the endpoint counts alone.
+            return Ok(None);
+        }
+        let fits = |at| state.clone().play_at(mv, Some(at)).is_ok();
+        if let Some(at) = self.selected_end.filter(|&at| fits(at)) {
+            return Ok(Some(at));
+        }
+        let ends: Vec<usize> = (0..)
+            .map_while(|at| state.end_pips(at).map(|_| at))
+            .filter(|&at| fits(at))
+            .collect();
+        match ends[..] {
+            [_, _, ..] => Err(format!(
+                "Several {} ends are open: pick the one it went on first.",
+                mv.end
+            )),
+            _ => Ok(ends.first().copied()),
+        }
+    }
+
+    /// Green or red over a drop target while a domino from the hand hovers over it, and the
+    /// play once it is dropped. `onto` turns the dragged domino into a move.
+    fn drop_target(
+        &mut self,
+        ui: &egui::Ui,
+        target: &egui::Response,
+        rect: Rect,
+        onto: impl Fn((u8, u8)) -> Option<(Move, Option<usize>)>,
+    ) {
+        if let Some(domino) = target.dnd_hover_payload::<(u8, u8)>() {
+            let fits = onto(*domino).is_some_and(|(mv, at)| self.check_play(mv, at).is_ok());
+            let color = if fits {
+                Color32::from_rgba_unmultiplied(0, 255, 0, 80)
+            } else {
+                Color32::from_rgba_unmultiplied(255, 0, 0, 80)
+            };
+            ui.painter().rect_filled(rect, 4.0, color);
+        }
+        if let Some(domino) = target.dnd_release_payload::<(u8, u8)>() {
+            let (min, max) = *domino;
+            match onto((min, max)) {
+                Some((mv, at)) => self.play_from_hand(mv, at),
+                None => self.info = format!("The {min} {max} does not fit there."),
+            }
+        }
+    }
+
+    /// Badge under a domino in the hand: whether playing it locks the opponents out, or
+    /// opens an end only they can use.
+    fn draw_block_badge(&self, ui: &mut egui::Ui, min: u8, max: u8) {
+        let blocks: Vec<Block> = [(min, max), (max, min)]
+            .into_iter()
+            .filter_map(|(end, open)| self.game_state.block(0, Move { end, open }))
+            .collect();
+        if blocks.contains(&Block::LockOut) {
+            ui.colored_label(Color32::DARK_GREEN, "Lock")
+                .on_hover_text("After this play no opponent can play on any end.");
+        } else if !blocks.is_empty()
+            && blocks
+                .iter()
+                .all(|&block| block == Block::OpensForOpponents)
+        {
+            ui.colored_label(ui.visuals().warn_fg_color, "Opens")
+                .on_hover_text("This play opens an end only your opponents can use.");
+        } else {
+            // Keep the dominoes lined up.
+            ui.label("");
+        }
+    }
+
+    /// Whether to play a double from the hand now or hold it, shown under the double while it
+    /// can be played.
+    fn draw_double_timing(&self, ui: &mut egui::Ui, pips: u8) {
+        let mv = Move {
+            end: pips,
+            open: pips,
+        };
+        if !self.game_state.rules().opens_feet() || self.game_state.block(0, mv).is_none() {
+            return;
+        }
+        let timing = self.game_state.double_timing(pips);
+        let mut details = vec![format!(
+            "{} unseen and {} more in your hand can cover it.",
+            timing.unseen, timing.toes
+        )];
+        for &(seat, chance) in &timing.stuck {
+            details.push(format!(
+                "{} draws: {:.0}%",
+                seat_name(seat.into()),
+                100.0 * chance
+            ));
+        }
+        details.push(format!(
+            "Opponents expected to draw: {:.1}",
+            timing.expected_draws()
+        ));
+        let verdict = if timing.play_now() {
+            "Play now"
+        } else {
+            "Hold"
+        };
+        ui.small(verdict).on_hover_text(details.join("\n"));
+    }
+
     fn draw_hand(&mut self, ui: &mut egui::Ui) {
         let mut frame = Frame::group(ui.style()).begin(ui);
         frame.content_ui.heading("Player's Hand");
-        frame
-            .content_ui
-            .label("Left click end to play. Right click to remove.");
+        frame.content_ui.label(if self.practice.is_some() {
+            "Left click end to play, or drag onto an end."
+        } else {
+            "Left click end to play, or drag onto an end. Right click to remove."
+        });
 
         // List of dominoes in the player's hand:
         frame.content_ui.horizontal_wrapped(|ui| {
             for (min, max) in self.game_state.hand.as_vector() {
                 let domino = ui
-                    .add_sized([60.0, 120.0], domino_image(min, max))
-                    .interact(egui::Sense::click());
+                    .vertical(|ui| {
+                        let domino = ui
+                            .add_sized([60.0, 120.0], domino_image(min, max))
+                            .interact(egui::Sense::click_and_drag());
+                        self.draw_block_badge(ui, min, max);
+                        if min == max {
+                            self.draw_double_timing(ui, min);
+                        }
+                        domino
+                    })
+                    .inner;
+                domino.dnd_set_drag_payload((min, max));
+                if let (true, Some(pointer)) = (domino.dragged(), domino.interact_pointer_pos()) {
+                    // Follow the pointer while the domino is dragged to an end.
+                    egui::Area::new(egui::Id::new("dragged_domino"))
+                        .order(egui::Order::Tooltip)
+                        .interactable(false)
+                        .fixed_pos(pointer - Vec2::new(30.0, 60.0))
+                        .show(ui.ctx(), |ui| {
+                            ui.add_sized([60.0, 120.0], domino_image(min, max));
+                        });
+                }
                 if domino.clicked() {
                     if let Some(Pos2 { x: _, y }) = domino.interact_pointer_pos() {
                         // Top or bottom of domino was clicked. Rotate and attempt to play.
-                        let state_copy = self.game_state.clone();
-                        if let Err(text) =
-                            self.game_state.play(min, max, y < domino.rect.center().y)
-                        {
-                            // Report invalid play.
-                            self.info = text;
-                        } else {
-                            // Play successful: remove domino from player's hand.
-                            self.stack.push(state_copy);
-                            self.game_state.hand.remove(min, max);
-                        }
+                        let min_matches = y < domino.rect.center().y;
+                        let picked = self
+                            .selected_end
+                            .and_then(|at| Some((at, self.game_state.end_pips(at)?)));
+                        // An end picked on the board decides which way the domino faces.
+                        let (end, open) = match picked {
+                            Some((_, pips)) if pips == max => (max, min),
+                            Some((_, pips)) if pips == min => (min, max),
+                            None if min_matches => (min, max),
+                            _ => (max, min),
+                        };
+                        self.play_from_hand(Move { end, open }, picked.map(|(at, _)| at));
                     }
-                } else if domino.secondary_clicked() {
+                } else if domino.secondary_clicked() && self.practice.is_none() {
                     // Domino was right clicked: remove from player's hand.
                     self.push_stack();
                     self.game_state.hand.remove(min, max);
@@ -432,7 +1089,11 @@ impl MainWindow {
             }
         });
 
-        // Add dominoes to player's hand.
+        // Add dominoes to player's hand. Practice games draw from the boneyard instead.
+        if self.practice.is_some() {
+            frame.end(ui);
+            return;
+        }
         frame.content_ui.horizontal(|ui| {
             ui.label("Draw:");
             let re = ui.text_edit_singleline(&mut self.text_edit);
@@ -443,6 +1104,27 @@ impl MainWindow {
                         (Ok(a), Ok(b)) if a <= PIP_MAX_U8 && b <= PIP_MAX_U8 => {
                             self.push_stack();
                             self.game_state.hand.add(a.min(b), a.max(b));
+                            if self.game_state.in_opening() && !self.game_state.drawing_for_double {
+                                // Dealt, not drawn.
+                                self.game_state.tiles[0] = self.game_state.hand.len() as u8;
+                            } else {
+                                if self.game_state.turn != 0 {
+                                    self.game_state.turn = 0;
+                                    self.game_state.draws = 0;
+                                }
+                                self.game_state.record_draw();
+                                self.notify_engine(0, Event::Draw(Some((a.min(b), a.max(b)))));
+                                let found = a == b
+                                    && self
+                                        .game_state
+                                        .double()
+                                        .as_ref()
+                                        .is_some_and(|d| d.pips == a);
+                                if self.game_state.drawing_for_double && !found {
+                                    // Still looking for the first double: next seat draws.
+                                    self.game_state.end_turn();
+                                }
+                            }
                             self.text_edit.clear();
                             self.info.clear();
                         }
@@ -459,15 +1141,166 @@ impl MainWindow {
                 }
                 re.request_focus();
             }
+            // Passing is only allowed once nothing plays and drawing is done.
+            let mut mine = self.game_state.clone();
+            if mine.turn != 0 {
+                mine.turn = 0;
+                mine.draws = 0;
+            }
+            let can_pass = mine.may_pass() && mine.legal_moves(&mine.hand).is_empty();
+            if ui
+                .add_enabled(can_pass, egui::Button::new("Pass"))
+                .on_disabled_hover_text("Play a domino, or draw first if the rules allow.")
+                .clicked()
+            {
+                self.push_stack();
+                self.game_state = mine;
+                self.game_state.record_pass();
+                self.notify_engine(0, Event::Pass);
+            }
         });
         frame.end(ui);
     }
 
+    /// Warning once an opponent may go out on their next turn, with the points the user would
+    /// be left holding and the damage-control play.
+    fn draw_danger(&mut self, ui: &mut egui::Ui) {
+        let danger = self.game_state.danger(0);
+        if danger.is_empty() || self.game_state.round_end().is_some() {
+            return;
+        }
+        let warn = ui.visuals().error_fg_color;
+        let mut frame = Frame::group(ui.style())
+            .stroke(Stroke::new(2.0, warn))
+            .begin(ui);
+        for seat in danger {
+            let tiles = self.game_state.tiles[usize::from(seat)];
+            frame.content_ui.heading(
+                egui::RichText::new(format!(
+                    "{} has {tiles} {} left!",
+                    seat_name(seat.into()),
+                    if tiles == 1 { "domino" } else { "dominoes" }
+                ))
+                .color(warn),
+            );
+        }
+        let held = self.game_state.hand.pips();
+        let mut state = self.game_state.clone();
+        state.turn = 0;
+        match search(&state, 2) {
+            Some(result) => {
+                let mv = result.pv[0];
+                let (min, max) = mv.domino();
+                frame.content_ui.label(format!(
+                    "If they go out next turn you eat {held} points, or {} after playing the {min} {max}.",
+                    held - mv.pips()
+                ));
+                frame.content_ui.horizontal(|ui| {
+                    ui.strong(format!(
+                        "Damage control: play the {min} {max} on a {} end.",
+                        mv.end
+                    ));
+                    if ui.button("Play It").clicked() {
+                        self.play_from_hand(mv, None);
+                    }
+                });
+            }
+            None => {
+                frame.content_ui.label(format!(
+                    "If they go out next turn you eat {held} points. Nothing in your hand plays."
+                ));
+            }
+        }
+        frame.end(ui);
+    }
+
+    /// Longest chain through the hand and the fastest way to shed pips, redone whenever the
+    /// hand or the table changes.
+    fn draw_plan(&mut self, ui: &mut egui::Ui) {
+        let state = &self.game_state;
+        let key = PlanKey::new(state);
+        if self
+            .plan
+            .as_ref()
+            .is_none_or(|(planned, _)| *planned != key)
+        {
+            let mut state = state.clone();
+            state.turn = 0;
+            self.plan = Some((key, plan(&state)));
+        }
+        let Some((_, plan)) = &self.plan else {
+            return;
+        };
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Plan");
+        if plan.longest.is_empty() {
+            frame
+                .content_ui
+                .label("Nothing in your hand plays right now.");
+        }
+        for (title, line) in [
+            ("Longest chain", &plan.longest),
+            ("Shed pips fastest", &plan.shedding),
+        ] {
+            if line.is_empty() {
+                continue;
+            }
+            let pips: u32 = line.iter().map(Move::pips).sum();
+            frame
+                .content_ui
+                .strong(format!("{title}: {} dominoes, {pips} pips", line.len()));
+            frame.content_ui.horizontal_wrapped(|ui| {
+                for (step, mv) in line.iter().enumerate() {
+                    ui.label(format!("{}. {}-{}", step + 1, mv.end, mv.open));
+                }
+            });
+        }
+        frame.end(ui);
+    }
+
     fn other_players(&mut self, ui: &mut egui::Ui) {
         let mut frame = Frame::group(ui.style()).begin(ui);
         frame.content_ui.heading("Other Players");
-        frame.content_ui.label("Enter matching end first.");
         frame.content_ui.horizontal(|ui| {
+            ui.label("Turn:");
+            let players = self.game_state.players;
+            if ui
+                .add(DragValue::new(&mut self.game_state.turn).range(0..=players - 1))
+                .changed()
+            {
+                self.game_state.draws = 0;
+            }
+            ui.label(self.game_state.turn_prompt());
+        });
+        let seat = self.game_state.turn;
+        frame
+            .content_ui
+            .add_enabled_ui(seat != 0, |ui| {
+                ui.label("Enter matching end first.");
+                self.other_player_actions(ui, seat);
+            })
+            .response
+            .on_disabled_hover_text("It is your turn. Change the turn if another seat is playing.");
+
+        // Pips each seat cannot hold, inferred from passes.
+        for seat in 1..self.game_state.players {
+            let excluded = self.game_state.excluded[usize::from(seat)];
+            if excluded != 0 {
+                let pips: Vec<String> = (0..=PIP_MAX_U8)
+                    .filter(|pips| excluded & 1 << pips != 0)
+                    .map(|pips| pips.to_string())
+                    .collect();
+                frame
+                    .content_ui
+                    .label(format!("Seat {seat} has no {}.", pips.join(", ")));
+            }
+        }
+        frame.end(ui);
+    }
+
+    /// Play, draw and pass entry for another seat.
+    fn other_player_actions(&mut self, ui: &mut egui::Ui, seat: u8) {
+        ui.horizontal(|ui| {
             ui.label("Play:");
             let re = ui.text_edit_singleline(&mut self.text_edit);
             if re.lost_focus() && re.ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
@@ -475,13 +1308,17 @@ impl MainWindow {
                 if parts.len() == 2 {
                     match (parts[0].parse::<u8>(), parts[1].parse::<u8>()) {
                         (Ok(a), Ok(b)) if a <= PIP_MAX_U8 && b <= PIP_MAX_U8 => {
+                            let mv = Move { end: a, open: b };
                             let state_copy = self.game_state.clone();
-                            if let Err(text) =
-                                self.game_state.play(a.min(b), a.max(b), a.min(b) == a)
+                            if let Err(text) = self
+                                .end_for(mv)
+                                .and_then(|at| self.game_state.record_play_at(mv, at))
                             {
                                 self.info = text;
                             } else {
                                 self.stack.push(state_copy);
+                                self.selected_end = None;
+                                self.notify_engine(seat, Event::Play(mv));
                                 self.text_edit.clear();
                                 self.info.clear();
                             }
@@ -500,15 +1337,392 @@ impl MainWindow {
                 re.request_focus();
             }
         });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.game_state.may_draw(), egui::Button::new("Drew"))
+                .clicked()
+            {
+                self.push_stack();
+                self.game_state.record_draw();
+                self.notify_engine(seat, Event::Draw(None));
+            }
+            if ui
+                .add_enabled(self.game_state.may_pass(), egui::Button::new("Passed"))
+                .clicked()
+            {
+                self.push_stack();
+                self.game_state.record_pass();
+                self.notify_engine(seat, Event::Pass);
+            }
+        });
+    }
+
+    fn draw_table(&mut self, ui: &mut egui::Ui) {
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Table");
+        frame
+            .content_ui
+            .add_enabled_ui(self.practice.is_none(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Players:");
+                    let partnerships = self.game_state.has_teams();
+                    if ui
+                        .add(
+                            DragValue::new(&mut self.game_state.players)
+                                .range(2..=MAX_PLAYERS as u8),
+                        )
+                        .changed()
+                    {
+                        self.game_state.set_partnerships(partnerships);
+                    }
+                    self.game_state.turn = self.game_state.turn.min(self.game_state.players - 1);
+                    ui.label("Boneyard:");
+                    ui.add(
+                        DragValue::new(&mut self.game_state.boneyard).range(0..=DOMINO_COUNT as u8),
+                    );
+                    if ui
+                        .button("Deal")
+                        .on_hover_text("Set the boneyard to what is left after dealing.")
+                        .clicked()
+                    {
+                        self.push_stack();
+                        self.game_state.deal_boneyard();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("When stuck:");
+                    for rule in DrawRule::ALL {
+                        ui.radio_value(&mut self.game_state.draw_rule, rule, rule.description());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Game:");
+                    let in_opening = self.game_state.in_opening();
+                    for variant in Variant::ALL {
+                        ui.add_enabled_ui(in_opening, |ui| {
+                            ui.radio_value(
+                                &mut self.game_state.variant,
+                                variant,
+                                variant.description(),
+                            )
+                        })
+                        .response
+                        .on_disabled_hover_text("Change games between rounds.");
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Doubles:");
+                    for rule in CoverRule::ALL {
+                        ui.radio_value(&mut self.game_state.cover_rule, rule, rule.description());
+                    }
+                });
+                let mut partnerships = self.game_state.has_teams();
+                let even = self.game_state.partnerships_possible();
+                if ui
+                    .add_enabled(
+                        even,
+                        egui::Checkbox::new(&mut partnerships, "Partners across the table"),
+                    )
+                    .on_disabled_hover_text(
+                        "Partnerships need an even number of seats, at least four.",
+                    )
+                    .changed()
+                {
+                    self.push_stack();
+                    self.game_state.set_partnerships(partnerships);
+                }
+            });
+        let (odds, playable, unseen) = self.game_state.draw_odds();
+        frame.content_ui.label(format!(
+            "{} dominoes left to draw. A draw is playable {:.0}% of the time ({playable} of {unseen} unseen dominoes fit).",
+            self.game_state.boneyard,
+            100.0 * odds
+        ));
+        for seat in 0..usize::from(self.game_state.players) {
+            frame.content_ui.label(format!(
+                "{}: {} dominoes, {} points",
+                seat_name(seat),
+                self.game_state.tiles[seat],
+                self.game_state.scores[seat]
+            ));
+        }
+        for (team, points) in self.team_totals(&self.game_state.scores) {
+            frame
+                .content_ui
+                .strong(format!("{team}: {points} points together"));
+        }
+        frame.end(ui);
+    }
+
+    /// Forward something that happened at the table to the engine, if one is running.
+    fn notify_engine(&mut self, seat: u8, event: Event) {
+        if let Some(engine) = &mut self.engine {
+            if let Err(error) = engine.send(&Command::Event { seat, event }) {
+                self.info = format!("Engine error: {error}");
+            }
+        }
+    }
+
+    /// Handle replies from the engine.
+    fn poll_engine(&mut self) {
+        let Some(engine) = &mut self.engine else {
+            return;
+        };
+        for reply in engine.poll() {
+            match reply {
+                Ok(Reply::Info(text)) => {
+                    self.engine_output.push(text);
+                    if self.engine_output.len() > 8 {
+                        self.engine_output.remove(0);
+                    }
+                }
+                Ok(Reply::BestMove(action)) => self.engine_suggestion = Some(action),
+                Ok(_) => {}
+                Err(text) => self.info = text,
+            }
+        }
+        if engine.exited {
+            self.info = format!("Engine {} exited.", engine.name);
+            self.engine = None;
+        }
+    }
+
+    fn draw_engine(&mut self, ui: &mut egui::Ui) {
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Engine");
+        frame.content_ui.horizontal(|ui| {
+            ui.label("Command:");
+            ui.text_edit_singleline(&mut self.engine_command);
+            if self.engine.is_none() && ui.button("Launch").clicked() {
+                match EngineHost::launch(&self.engine_command) {
+                    Ok(engine) => {
+                        self.engine = Some(engine);
+                        self.engine_output.clear();
+                        self.engine_suggestion = None;
+                        self.info.clear();
+                    }
+                    Err(error) => self.info = format!("Could not launch engine: {error}"),
+                }
+            } else if self.engine.is_some() && ui.button("Stop").clicked() {
+                self.engine = None;
+            }
+        });
+        if let Some(engine) = &mut self.engine {
+            let status = if engine.ready { "ready" } else { "starting" };
+            frame
+                .content_ui
+                .label(format!("{} ({status})", engine.name));
+            if frame.content_ui.button("Suggest Move").clicked() {
+                self.engine_suggestion = None;
+                let rules = [
+                    ("draw", self.game_state.draw_rule.name()),
+                    ("cover", self.game_state.cover_rule.name()),
+                    ("game", self.game_state.variant.name()),
+                ];
+                let sent = rules
+                    .into_iter()
+                    .try_for_each(|(name, value)| {
+                        engine.send(&Command::Rule {
+                            name: name.to_owned(),
+                            value: value.to_owned(),
+                        })
+                    })
+                    .and_then(|()| engine.send(&Command::Position(self.game_state.clone())))
+                    .and_then(|()| engine.send(&Command::Go));
+                if let Err(error) = sent {
+                    self.info = format!("Engine error: {error}");
+                }
+            }
+        }
+        match self.engine_suggestion {
+            Some(Action::Play(mv)) => {
+                frame.content_ui.horizontal(|ui| {
+                    ui.label(format!("Suggested: play {} on a {} end.", mv.open, mv.end));
+                    let (min, max) = mv.domino();
+                    ui.add_sized([30.0, 60.0], domino_image(min, max));
+                });
+            }
+            Some(Action::Draw) => {
+                frame.content_ui.label("Suggested: draw.");
+            }
+            Some(Action::Pass) => {
+                frame.content_ui.label("Suggested: pass.");
+            }
+            None => {}
+        }
+        for line in &self.engine_output {
+            frame.content_ui.monospace(line);
+        }
+        frame.end(ui);
+    }
+
+    fn start_practice(&mut self, practice: Result<Practice, String>) {
+        match practice {
+            Ok(practice) => {
+                if self.practice_saved_state.is_none() {
+                    self.practice_saved_state = Some(self.game_state.clone());
+                }
+                self.practice = Some(practice);
+                self.info.clear();
+            }
+            Err(text) => self.info = text,
+        }
+    }
+
+    /// Mirror the practice round into `game_state` and let bots take their turns.
+    fn step_practice(&mut self, ctx: &egui::Context) {
+        let Some(practice) = &mut self.practice else {
+            return;
+        };
+        let time = ctx.input(|i| i.time);
+        if time >= self.practice_next_step && practice.step_bot() {
+            // Pause between bot actions so the human can follow along.
+            self.practice_next_step = time + 0.6;
+        }
+        if !practice.human_to_move() && practice.round.end().is_none() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        self.game_state = practice.round.observe(0).state;
+    }
+
+    fn draw_practice(&mut self, ui: &mut egui::Ui) {
+        let mut frame = Frame::group(ui.style()).begin(ui);
+        frame.content_ui.heading("Practice Against the Computer");
+        frame.content_ui.horizontal(|ui| {
+            ui.label("Bots:");
+            ui.add(DragValue::new(&mut self.practice_bots).range(1..=7));
+            egui::ComboBox::from_id_source("practice_strategy")
+                .selected_text(STRATEGY_NAMES[self.practice_strategy])
+                .show_index(ui, &mut self.practice_strategy, STRATEGY_NAMES.len(), |i| {
+                    STRATEGY_NAMES[i]
+                });
+            ui.label("Seed:")
+                .on_hover_text("Leave empty for a random deal.");
+            ui.add(egui::TextEdit::singleline(&mut self.practice_seed).desired_width(160.0));
+            if ui.button("New Game").clicked() {
+                let seed = match self.practice_seed.trim() {
+                    "" => Ok(Rng::from_time().next_u64()),
+                    seed => seed
+                        .parse()
+                        .map_err(|_| "Seed must be a whole number.".to_owned()),
+                };
+                let bots = vec![STRATEGY_NAMES[self.practice_strategy]; self.practice_bots];
+                self.start_practice(seed.and_then(|seed| {
+                    Practice::new(
+                        &bots,
+                        seed,
+                        self.game_state.draw_rule,
+                        self.game_state.variant,
+                    )
+                }));
+            }
+            if self.practice.is_some() && ui.button("Back to Assistant").clicked() {
+                self.practice = None;
+                if let Some(state) = self.practice_saved_state.take() {
+                    self.game_state = state;
+                }
+            }
+        });
+        frame.content_ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.practice_path);
+            if let Some(practice) = &self.practice {
+                if ui.button("Save").clicked() {
+                    self.info = match std::fs::write(&self.practice_path, practice.save()) {
+                        Ok(()) => format!("Saved to {}.", self.practice_path),
+                        Err(error) => format!("Could not save: {error}"),
+                    };
+                }
+            }
+            if ui.button("Load").clicked() {
+                let practice = std::fs::read_to_string(&self.practice_path)
+                    .map_err(|error| format!("Could not load: {error}"))
+                    .and_then(|text| Practice::load(&text));
+                self.start_practice(practice);
+            }
+        });
+
+        if let Some(practice) = &mut self.practice {
+            let round = &practice.round;
+            frame.content_ui.horizontal(|ui| {
+                ui.label(format!("Seed: {}", practice.seed));
+                if ui.small_button("Copy").clicked() {
+                    ui.output_mut(|o| o.copied_text = practice.seed.to_string());
+                }
+            });
+            frame.content_ui.label(format!(
+                "Boneyard: {}. {} to move.",
+                round.boneyard.len(),
+                seat_name(round.turn())
+            ));
+            if let Some(double) = &round.state.double() {
+                frame.content_ui.label(format!(
+                    "Cover the {} {} double: {} / {} played.",
+                    double.pips,
+                    double.pips,
+                    double.count,
+                    double.max_count()
+                ));
+            }
+            for (seat, bot) in practice.bots.iter().enumerate() {
+                let seat = seat + 1;
+                frame.content_ui.label(format!(
+                    "{} ({}): {} dominoes",
+                    seat_name(seat),
+                    bot.name(),
+                    round.hands[seat].len()
+                ));
+            }
+            if let Some(end) = round.end() {
+                let text = match end {
+                    RoundEnd::Out(seat) => format!("{} went out.", seat_name(seat)),
+                    RoundEnd::Blocked => "The game is blocked.".to_owned(),
+                };
+                frame.content_ui.strong(text);
+                for (seat, hand) in round.hands.iter().enumerate() {
+                    frame.content_ui.label(format!(
+                        "{}: {} pips left",
+                        seat_name(seat),
+                        hand.pips()
+                    ));
+                }
+            } else if practice.human_to_move() {
+                frame.content_ui.horizontal(|ui| {
+                    for (label, action) in [("Draw", Action::Draw), ("Pass", Action::Pass)] {
+                        if ui.button(label).clicked() {
+                            if let Err(text) = practice.apply(action) {
+                                self.info = text;
+                            } else {
+                                self.info.clear();
+                            }
+                        }
+                    }
+                });
+            }
+            let skip = practice.log.len().saturating_sub(10);
+            for line in &practice.log[skip..] {
+                frame.content_ui.label(line);
+            }
+        }
         frame.end(ui);
     }
 }
 
 impl eframe::App for MainWindow {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_engine();
+        if self.engine.is_some() {
+            // Keep polling while the engine may be thinking.
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        self.step_practice(ctx);
+
         ctx.input_mut(|i| {
             if i.consume_shortcut(&UNDO_SHORTCUT) {
-                if let Some(new_state) = self.stack.pop() {
+                if self.practice.is_some() {
+                    self.info.replace_with("Practice games cannot be undone.")
+                } else if let Some(new_state) = self.stack.pop() {
                     self.game_state = new_state;
                     self.info = format!("{} undo frames remain.", self.stack.len());
                 } else {
@@ -520,24 +1734,64 @@ impl eframe::App for MainWindow {
         egui::SidePanel::left("played_dominoes")
             .exact_width(ctx.screen_rect().width().min(ctx.screen_rect().height()) / 2.0)
             .show(ctx, |ui| {
-                // Draw played dominoes.
-                self.draw_played_dominoes(ui);
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.board_view, false, "Grid");
+                    ui.selectable_value(&mut self.board_view, true, "Board");
+                    if self.board_view && ui.button("Fit").clicked() {
+                        self.board_fit = true;
+                    }
+                });
+                if self.board_view {
+                    self.draw_board(ui);
+                } else {
+                    // Draw played dominoes.
+                    self.draw_played_dominoes(ui);
+                    if self.practice.is_none() {
+                        ui.checkbox(&mut self.raw_edit, "Raw edit")
+                            .on_hover_text("Toggle dominoes without recording a play.");
+                        self.draw_grid_play(ui);
+                    }
+                }
             });
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.label(&self.info);
             ScrollArea::vertical().show(ui, |ui| {
+                // Game against the computer
+                self.draw_practice(ui);
+
                 // Start or double/chickenfoot
-                self.draw_double(ui);
+                if self.practice.is_none() {
+                    self.draw_round_end(ui);
+                    self.draw_double(ui);
+                }
+
+                // Opponents about to go out
+                self.draw_danger(ui);
 
                 // Player's hand
                 self.draw_hand(ui);
+                self.draw_plan(ui);
 
                 // Other players' play
-                self.other_players(ui);
+                if self.practice.is_none() {
+                    self.other_players(ui);
+                }
+
+                // Endpoints, or trains in Mexican Train
+                match self.game_state.rules().layout() {
+                    Layout::Endpoints => self.draw_endpoints(ui),
+                    Layout::Trains => self.draw_trains(ui),
+                }
+
+                // Where each pip value is
+                self.draw_suits(ui);
+
+                // Players and boneyard
+                self.draw_table(ui);
 
-                // Endpoints
-                self.draw_endpoints(ui);
+                // External engine
+                self.draw_engine(ui);
             });
         });
     }
@@ -0,0 +1,240 @@
+use crate::deal::Deal;
+use crate::game::{Action, DrawRule, Move, Variant};
+use crate::rng::Rng;
+use crate::round::{Round, RoundEnd};
+use crate::strategy::{strategy_by_name, Strategy};
+use crate::PIP_MAX_U8;
+
+/// A game against the computer. Seat 0 is the human; seat `i` is played by `bots[i - 1]`.
+pub struct Practice {
+    /// Seed the round was dealt from. See `Deal`.
+    pub seed: u64,
+    pub round: Round,
+    pub bots: Vec<Box<dyn Strategy>>,
+    /// Every action taken since the deal, oldest first, with the board tile played on if it
+    /// was picked.
+    pub actions: Vec<(Action, Option<usize>)>,
+    /// What happened, oldest first.
+    pub log: Vec<String>,
+}
+
+impl Practice {
+    /// Deal a new round of double-twelve `variant` from `seed`, opening with the double
+    /// twelve. Bots are named as in `strategy_by_name`.
+    pub fn new(
+        bot_names: &[&str],
+        seed: u64,
+        draw_rule: DrawRule,
+        variant: Variant,
+    ) -> Result<Self, String> {
+        let deal = Deal::new(seed, bot_names.len() + 1);
+        let mut rng = deal.game_rng();
+        let bots = bot_names
+            .iter()
+            .map(|name| {
+                strategy_by_name(name, Rng::new(rng.next_u64()))
+                    .ok_or_else(|| format!("Unknown strategy {name}."))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let round = Round::new(deal, PIP_MAX_U8, draw_rule, variant);
+        let players = bots.len() + 1;
+        let starter = (round.turn() + players - 1) % players;
+        Ok(Self {
+            seed,
+            round,
+            bots,
+            actions: vec![],
+            log: vec![
+                format!("Dealt from seed {seed}."),
+                format!(
+                    "{} opened with the {PIP_MAX_U8} {PIP_MAX_U8}.",
+                    seat_name(starter)
+                ),
+            ],
+        })
+    }
+
+    /// Saved game: the seed, the bots, the rules and every action, one per line.
+    pub fn save(&self) -> String {
+        let mut text = format!("seed {}\nbots", self.seed);
+        for bot in &self.bots {
+            text.push(' ');
+            text.push_str(bot.name());
+        }
+        text.push_str(&format!(
+            "\nrule draw {}\nrule game {}\n",
+            self.round.state.draw_rule.name(),
+            self.round.state.variant.name()
+        ));
+        for (action, at) in &self.actions {
+            match action {
+                Action::Play(Move { end, open }) => match at {
+                    Some(at) => text.push_str(&format!("play {end} {open} {at}\n")),
+                    None => text.push_str(&format!("play {end} {open}\n")),
+                },
+                Action::Draw => text.push_str("draw\n"),
+                Action::Pass => text.push_str("pass\n"),
+            }
+        }
+        text
+    }
+
+    /// Replay a game written by `save`. The bots choose their actions again, which must match
+    /// the saved ones.
+    pub fn load(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().peekable();
+        let seed = lines
+            .next()
+            .and_then(|line| line.strip_prefix("seed "))
+            .and_then(|seed| seed.trim().parse().ok())
+            .ok_or("Saved game must start with its seed.")?;
+        let bot_names: Vec<&str> = lines
+            .next()
+            .and_then(|line| line.strip_prefix("bots"))
+            .ok_or("Saved game must list its bots.")?
+            .split_whitespace()
+            .collect();
+        let draw_rule = match lines.next_if(|line| line.starts_with("rule draw ")) {
+            Some(line) => DrawRule::from_name(line["rule draw ".len()..].trim())
+                .ok_or_else(|| format!("Unknown rule: {line}"))?,
+            None => DrawRule::default(),
+        };
+        let variant = match lines.next_if(|line| line.starts_with("rule game ")) {
+            Some(line) => Variant::from_name(line["rule game ".len()..].trim())
+                .ok_or_else(|| format!("Unknown rule: {line}"))?,
+            None => Variant::default(),
+        };
+        let mut practice = Self::new(&bot_names, seed, draw_rule, variant)?;
+        for line in lines {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let (action, at) = match tokens[..] {
+                ["play", end, open, ..] if tokens.len() <= 4 => {
+                    let at = match tokens.get(3).map(|at| at.parse()) {
+                        Some(Ok(at)) => Some(at),
+                        Some(Err(_)) => return Err(format!("Invalid play: {line}")),
+                        None => None,
+                    };
+                    match (end.parse(), open.parse()) {
+                        (Ok(end), Ok(open)) if end <= PIP_MAX_U8 && open <= PIP_MAX_U8 => {
+                            (Action::Play(Move { end, open }), at)
+                        }
+                        _ => return Err(format!("Invalid play: {line}")),
+                    }
+                }
+                ["draw"] => (Action::Draw, None),
+                ["pass"] => (Action::Pass, None),
+                [] => continue,
+                _ => return Err(format!("Unknown action: {line}")),
+            };
+            if practice.round.turn() == 0 {
+                practice.apply_at(action, at)?;
+            } else {
+                // Let the bot choose again so its state, e.g. its `Rng`, moves on as it did.
+                let done = practice.actions.len();
+                if !practice.step_bot() || practice.actions[done..] != [(action, at)] {
+                    return Err(format!("The bots did not make this play: {line}"));
+                }
+            }
+        }
+        Ok(practice)
+    }
+
+    pub fn human_to_move(&self) -> bool {
+        self.round.turn() == 0 && self.round.end().is_none()
+    }
+
+    /// Perform `action` for the seat to move and log it. Did nothing if `Err` is returned.
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        self.apply_at(action, None)
+    }
+
+    /// `apply`, playing on tile `at` of the board. See `GameState::play_at`.
+    pub fn apply_at(&mut self, action: Action, at: Option<usize>) -> Result<(), String> {
+        let seat = self.round.turn();
+        self.round.apply_at(action, at)?;
+        self.actions.push((action, at));
+        let text = match action {
+            Action::Play(mv) => {
+                let (min, max) = mv.domino();
+                format!("played the {min}-{max} on a {}", mv.end)
+            }
+            Action::Draw => "drew".to_owned(),
+            Action::Pass => "passed".to_owned(),
+        };
+        self.log.push(format!("{} {text}.", seat_name(seat)));
+        match self.round.end() {
+            Some(RoundEnd::Out(seat)) => self.log.push(format!("{} went out.", seat_name(seat))),
+            Some(RoundEnd::Blocked) => self.log.push("The game is blocked.".to_owned()),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Let the bot to move take one action. Returns `false` if it is not a bot's turn.
+    pub fn step_bot(&mut self) -> bool {
+        if self.round.turn() == 0 || self.round.end().is_some() {
+            return false;
+        }
+        let seat = self.round.turn();
+        let action = self.bots[seat - 1].choose(&self.round.observe(seat));
+        if let Err(text) = self.apply(action) {
+            // A bot asked for something illegal: fall back to the least it must do.
+            self.log.push(format!(
+                "{} tried an illegal action: {text}",
+                seat_name(seat)
+            ));
+            let fallback = self.round.observe(seat).stuck_action();
+            if self.apply(fallback).is_err() {
+                let moves = self.round.observe(seat).legal_moves();
+                let _ = self.apply(Action::Play(moves[0]));
+            }
+        }
+        true
+    }
+}
+
+pub fn seat_name(seat: usize) -> String {
+    if seat == 0 {
+        "You".to_owned()
+    } else {
+        format!("Seat {seat}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_game_continues_like_the_original() {
+        let mut original = Practice::new(
+            &["random", "random"],
+            7,
+            DrawRule::default(),
+            Variant::default(),
+        )
+        .unwrap();
+        let step = |practice: &mut Practice| {
+            if !practice.step_bot() {
+                let seat = practice.round.observe(0);
+                let action = match seat.legal_moves().first() {
+                    Some(&mv) => Action::Play(mv),
+                    None => seat.stuck_action(),
+                };
+                practice.apply(action).unwrap();
+            }
+        };
+        for _ in 0..12 {
+            step(&mut original);
+        }
+        let mut loaded = Practice::load(&original.save()).unwrap();
+        for _ in 0..12 {
+            if original.round.end().is_some() {
+                break;
+            }
+            step(&mut original);
+            step(&mut loaded);
+        }
+        assert_eq!(loaded.actions, original.actions);
+    }
+}
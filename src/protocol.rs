@@ -0,0 +1,491 @@
+//! Chickenfoot Engine Protocol (CEP): a line-based text protocol between a user interface
+//! and an engine process, in the spirit of UCI for chess. Every message is a single line
+//! of whitespace-separated tokens. Dominoes are written `min-max`, e.g. `3-12`.
+//!
+//! Interface to engine:
+//! - `cep`: Handshake. The engine answers with `id` lines followed by `cepok`.
+//! - `isready`: The engine answers `readyok` once it has processed every earlier message.
+//! - `newgame`: Forget everything about the previous game.
+//! - `rule <name> <value>`: Set a rule, e.g. `rule draw until-playable`,
+//!   `rule cover in-order` or `rule game mexican-train`. Unknown rules are ignored.
+//! - `position [double <pips> <count> first|foot|none] [endpoints <13 counts>]
+//!   [played <dominoes>] [hand <dominoes>] [players <count>] [boneyard <count>]
+//!   [turn <seat>] [draws <count>] [trains <ends>] [teams <teams>] [excluded <masks>]
+//!   [tiles <counts>]`: Replace the engine's position.
+//!   `double` may repeat, oldest first, when several doubles are waiting to be covered.
+//!   `draws` is the number of dominoes the seat to move has drawn this turn. `trains` lists
+//!   the Mexican Train ends, one per seat and then the Mexican train, each followed by `*`
+//!   if it has a marker and `!` if it ends in an uncovered double, e.g. `trains 5* 12 7!`.
+//!   `teams` gives each seat's team, e.g. `teams 0 1 0 1` for partners across the table;
+//!   without it every seat plays for itself. `excluded` gives, per seat, a bit mask of pips
+//!   the seat is known not to hold because it passed. `tiles` gives the number of
+//!   dominoes in each seat's hand.
+//! - `event <seat> play <end> <open>`, `event <seat> draw [<domino>]`, `event <seat> pass`:
+//!   Something happened at the table. Seat 0 is the user.
+//! - `go`: Search the current position. The engine answers with `bestmove`.
+//! - `stop`: Answer `bestmove` as soon as possible. Ignored when no search is running.
+//! - `quit`: Exit.
+//!
+//! Engine to interface:
+//! - `id name <name>`, `id author <author>`
+//! - `cepok`, `readyok`
+//! - `info <text>`: Free text shown to the user.
+//! - `bestmove play <end> <open>`, `bestmove draw`, `bestmove pass`
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::{Action, DominoSet, DoubleDomino, GameState, Move, Train};
+use crate::{MAX_PLAYERS, PIP_MAX_U8, PIP_MAX_USIZE};
+
+/// Something that happened at the table, reported with `event`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Play(Move),
+    /// A domino was drawn from the boneyard. Only known if the user drew it.
+    Draw(Option<(u8, u8)>),
+    Pass,
+}
+
+/// Message from the interface to the engine.
+#[derive(Clone)]
+pub enum Command {
+    Cep,
+    IsReady,
+    NewGame,
+    Rule { name: String, value: String },
+    Position(GameState),
+    Event { seat: u8, event: Event },
+    Go,
+    Stop,
+    Quit,
+}
+
+/// Message from the engine to the interface.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Reply {
+    Id { key: String, value: String },
+    CepOk,
+    ReadyOk,
+    Info(String),
+    BestMove(Action),
+}
+
+fn parse_pips(token: Option<&str>) -> Result<u8, String> {
+    match token.map(str::parse::<u8>) {
+        Some(Ok(pips)) if pips <= PIP_MAX_U8 => Ok(pips),
+        Some(_) => Err(format!("Pips must be a number 0-{PIP_MAX_U8}.")),
+        None => Err("Missing pips.".to_owned()),
+    }
+}
+
+fn parse_domino(token: &str) -> Result<(u8, u8), String> {
+    match token.split_once('-') {
+        Some((a, b)) => {
+            let (a, b) = (parse_pips(Some(a))?, parse_pips(Some(b))?);
+            Ok((a.min(b), a.max(b)))
+        }
+        None => Err(format!("Domino must be written as min-max, not {token}.")),
+    }
+}
+
+fn parse_move<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<Move, String> {
+    Ok(Move {
+        end: parse_pips(tokens.next())?,
+        open: parse_pips(tokens.next())?,
+    })
+}
+
+fn parse_position<'a>(tokens: impl Iterator<Item = &'a str>) -> Result<GameState, String> {
+    let mut state = GameState {
+        doubles: vec![],
+        ..GameState::default()
+    };
+    let mut tokens = tokens.peekable();
+    while let Some(section) = tokens.next() {
+        match section {
+            "double" => {
+                let first = tokens.next();
+                if first == Some("none") {
+                    state.doubles.clear();
+                    continue;
+                }
+                let pips = parse_pips(first)?;
+                let count = parse_pips(tokens.next())?;
+                let first = match tokens.next() {
+                    Some("first") => true,
+                    Some("foot") => false,
+                    _ => return Err("Double must be followed by first or foot.".to_owned()),
+                };
+                let double_domino = DoubleDomino { pips, count, first };
+                if count >= double_domino.max_count() {
+                    return Err(format!(
+                        "Double {pips} is covered after {} plays, not {count}.",
+                        double_domino.max_count()
+                    ));
+                }
+                state.doubles.push(double_domino);
+            }
+            "endpoints" => {
+                // Each end showing some pips is a different domino with those pips.
+                for pips in 0..=PIP_MAX_USIZE {
+                    state.endpoints[pips] = tokens
+                        .next()
+                        .and_then(|token| token.parse().ok())
+                        .filter(|&count| count <= PIP_MAX_U8 + 1)
+                        .ok_or_else(|| {
+                            format!(
+                                "Endpoints needs {} counts of 0-{}.",
+                                PIP_MAX_USIZE + 1,
+                                PIP_MAX_USIZE + 1
+                            )
+                        })?;
+                }
+            }
+            "played" | "hand" => {
+                let set = if section == "played" {
+                    &mut state.played
+                } else {
+                    &mut state.hand
+                };
+                while let Some(token) = tokens.next_if(|token| token.contains('-')) {
+                    let (min, max) = parse_domino(token)?;
+                    set.add(min, max);
+                }
+            }
+            "trains" => {
+                while let Some(token) =
+                    tokens.next_if(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+                {
+                    let end = token.trim_end_matches(['*', '!']);
+                    state.trains.push(Train {
+                        end: parse_pips(Some(end))?,
+                        marker: token.contains('*'),
+                        double: token.contains('!'),
+                    });
+                }
+            }
+            "teams" | "excluded" | "tiles" => {
+                let mut seat = 0;
+                while let Some(token) =
+                    tokens.next_if(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+                {
+                    if seat >= MAX_PLAYERS {
+                        return Err(format!("{section} has more than {MAX_PLAYERS} seats."));
+                    }
+                    let value = token
+                        .parse()
+                        .map_err(|_| format!("{section} needs numbers, not {token}."))?;
+                    match section {
+                        "excluded" => state.excluded[seat] = value,
+                        _ => {
+                            let value = u8::try_from(value)
+                                .map_err(|_| format!("{section} {value} is out of range."))?;
+                            if section == "teams" {
+                                state.teams[seat] = value;
+                            } else {
+                                state.tiles[seat] = value;
+                            }
+                        }
+                    }
+                    seat += 1;
+                }
+            }
+            "players" | "boneyard" | "turn" | "draws" => {
+                let count = tokens
+                    .next()
+                    .and_then(|token| token.parse().ok())
+                    .ok_or_else(|| format!("{section} needs a number."))?;
+                match section {
+                    "players" => state.players = count,
+                    "boneyard" => state.boneyard = count,
+                    "turn" => state.turn = count,
+                    _ => state.draws = count,
+                }
+            }
+            _ => return Err(format!("Unknown position section {section}.")),
+        }
+    }
+    if !(2..=MAX_PLAYERS as u8).contains(&state.players) {
+        return Err(format!("Players must be 2-{MAX_PLAYERS}."));
+    }
+    if state.turn >= state.players {
+        return Err(format!("Turn {} is not a seat at the table.", state.turn));
+    }
+    Ok(state)
+}
+
+fn write_dominoes(f: &mut fmt::Formatter<'_>, set: &DominoSet) -> fmt::Result {
+    for (min, max) in set.as_vector() {
+        write!(f, " {min}-{max}")?;
+    }
+    Ok(())
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("cep") => Ok(Command::Cep),
+            Some("isready") => Ok(Command::IsReady),
+            Some("newgame") => Ok(Command::NewGame),
+            Some("rule") => match (tokens.next(), tokens.next()) {
+                (Some(name), Some(value)) => Ok(Command::Rule {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                }),
+                _ => Err("Rule needs a name and a value.".to_owned()),
+            },
+            Some("position") => Ok(Command::Position(parse_position(tokens)?)),
+            Some("event") => {
+                let seat = tokens
+                    .next()
+                    .and_then(|token| token.parse().ok())
+                    .filter(|&seat: &u8| usize::from(seat) < MAX_PLAYERS)
+                    .ok_or(format!("Event needs a seat number 0-{}.", MAX_PLAYERS - 1))?;
+                let event = match tokens.next() {
+                    Some("play") => Event::Play(parse_move(&mut tokens)?),
+                    Some("draw") => Event::Draw(tokens.next().map(parse_domino).transpose()?),
+                    Some("pass") => Event::Pass,
+                    _ => return Err("Event must be play, draw or pass.".to_owned()),
+                };
+                Ok(Command::Event { seat, event })
+            }
+            Some("go") => Ok(Command::Go),
+            Some("stop") => Ok(Command::Stop),
+            Some("quit") => Ok(Command::Quit),
+            Some(other) => Err(format!("Unknown command {other}.")),
+            None => Err("Empty command.".to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Cep => write!(f, "cep"),
+            Command::IsReady => write!(f, "isready"),
+            Command::NewGame => write!(f, "newgame"),
+            Command::Rule { name, value } => write!(f, "rule {name} {value}"),
+            Command::Position(state) => {
+                write!(f, "position")?;
+                if state.doubles.is_empty() {
+                    write!(f, " double none")?;
+                }
+                for DoubleDomino { pips, count, first } in &state.doubles {
+                    let kind = if *first { "first" } else { "foot" };
+                    write!(f, " double {pips} {count} {kind}")?;
+                }
+                write!(f, " endpoints")?;
+                for count in state.endpoints {
+                    write!(f, " {count}")?;
+                }
+                write!(f, " played")?;
+                write_dominoes(f, &state.played)?;
+                write!(f, " hand")?;
+                write_dominoes(f, &state.hand)?;
+                write!(
+                    f,
+                    " players {} boneyard {} turn {} draws {}",
+                    state.players, state.boneyard, state.turn, state.draws
+                )?;
+                if !state.trains.is_empty() {
+                    write!(f, " trains")?;
+                }
+                for train in &state.trains {
+                    let marker = if train.marker { "*" } else { "" };
+                    let double = if train.double { "!" } else { "" };
+                    write!(f, " {}{marker}{double}", train.end)?;
+                }
+                let seats = usize::from(state.players).min(MAX_PLAYERS);
+                if state.has_teams() {
+                    write!(f, " teams")?;
+                    for team in &state.teams[..seats] {
+                        write!(f, " {team}")?;
+                    }
+                }
+                write!(f, " tiles")?;
+                for tiles in &state.tiles[..seats] {
+                    write!(f, " {tiles}")?;
+                }
+                if state.excluded[..seats].iter().any(|&mask| mask != 0) {
+                    write!(f, " excluded")?;
+                    for mask in &state.excluded[..seats] {
+                        write!(f, " {mask}")?;
+                    }
+                }
+                Ok(())
+            }
+            Command::Event { seat, event } => match event {
+                Event::Play(Move { end, open }) => write!(f, "event {seat} play {end} {open}"),
+                Event::Draw(Some((min, max))) => write!(f, "event {seat} draw {min}-{max}"),
+                Event::Draw(None) => write!(f, "event {seat} draw"),
+                Event::Pass => write!(f, "event {seat} pass"),
+            },
+            Command::Go => write!(f, "go"),
+            Command::Stop => write!(f, "stop"),
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Reply {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim_start();
+        match keyword {
+            "id" => match rest.split_once(' ') {
+                Some((key, value)) => Ok(Reply::Id {
+                    key: key.to_owned(),
+                    value: value.trim().to_owned(),
+                }),
+                None => Err("Id needs a key and a value.".to_owned()),
+            },
+            "cepok" => Ok(Reply::CepOk),
+            "readyok" => Ok(Reply::ReadyOk),
+            "info" => Ok(Reply::Info(rest.to_owned())),
+            "bestmove" => {
+                let mut tokens = rest.split_whitespace();
+                match tokens.next() {
+                    Some("play") => Ok(Reply::BestMove(Action::Play(parse_move(&mut tokens)?))),
+                    Some("draw") => Ok(Reply::BestMove(Action::Draw)),
+                    Some("pass") => Ok(Reply::BestMove(Action::Pass)),
+                    _ => Err("Best move must be play, draw or pass.".to_owned()),
+                }
+            }
+            other => Err(format!("Unknown reply {other}.")),
+        }
+    }
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Id { key, value } => write!(f, "id {key} {value}"),
+            Reply::CepOk => write!(f, "cepok"),
+            Reply::ReadyOk => write!(f, "readyok"),
+            Reply::Info(text) => write!(f, "info {text}"),
+            Reply::BestMove(Action::Play(Move { end, open })) => {
+                write!(f, "bestmove play {end} {open}")
+            }
+            Reply::BestMove(Action::Draw) => write!(f, "bestmove draw"),
+            Reply::BestMove(Action::Pass) => write!(f, "bestmove pass"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_round_trip() {
+        let mut state = GameState {
+            doubles: vec![
+                DoubleDomino {
+                    pips: 12,
+                    count: 2,
+                    first: true,
+                },
+                DoubleDomino {
+                    pips: 7,
+                    count: 0,
+                    first: false,
+                },
+            ],
+            players: 4,
+            boneyard: 20,
+            turn: 2,
+            draws: 1,
+            trains: vec![
+                Train {
+                    end: 5,
+                    marker: true,
+                    double: false,
+                },
+                Train {
+                    end: 7,
+                    marker: false,
+                    double: true,
+                },
+            ],
+            ..GameState::default()
+        };
+        state.endpoints[3] = 2;
+        state.played.add(3, 12);
+        state.played.add(12, 12);
+        state.hand.add(0, 9);
+        state.set_partnerships(true);
+        state.excluded[1] = 1 << 4;
+        state.tiles[3] = 2;
+        let commands = [
+            Command::Cep,
+            Command::IsReady,
+            Command::NewGame,
+            Command::Rule {
+                name: "draw".to_owned(),
+                value: "until-playable".to_owned(),
+            },
+            Command::Position(state),
+            Command::Event {
+                seat: 1,
+                event: Event::Play(Move { end: 3, open: 8 }),
+            },
+            Command::Event {
+                seat: 0,
+                event: Event::Draw(Some((2, 6))),
+            },
+            Command::Event {
+                seat: 2,
+                event: Event::Draw(None),
+            },
+            Command::Event {
+                seat: 3,
+                event: Event::Pass,
+            },
+            Command::Go,
+            Command::Stop,
+            Command::Quit,
+        ];
+        for command in commands {
+            let line = command.to_string();
+            let parsed: Command = line.parse().unwrap();
+            assert_eq!(parsed.to_string(), line);
+        }
+    }
+
+    #[test]
+    fn replies_round_trip() {
+        let replies = [
+            Reply::Id {
+                key: "name".to_owned(),
+                value: "chicken-engine 0.1".to_owned(),
+            },
+            Reply::CepOk,
+            Reply::ReadyOk,
+            Reply::Info("depth 3 nodes 9".to_owned()),
+            Reply::BestMove(Action::Play(Move { end: 12, open: 4 })),
+            Reply::BestMove(Action::Draw),
+            Reply::BestMove(Action::Pass),
+        ];
+        for reply in replies {
+            assert_eq!(reply.to_string().parse(), Ok(reply));
+        }
+    }
+
+    #[test]
+    fn impossible_counts_are_rejected() {
+        let endpoints = |last: &str| format!("position endpoints 0 0 0 0 0 0 0 0 0 0 0 0 {last}");
+        assert!(endpoints("13").parse::<Command>().is_ok());
+        assert!(endpoints("14").parse::<Command>().is_err());
+        assert!("position double 12 3 first".parse::<Command>().is_ok());
+        assert!("position double 12 4 first".parse::<Command>().is_err());
+        assert!("position double 7 2 foot".parse::<Command>().is_ok());
+        assert!("position double 7 3 foot".parse::<Command>().is_err());
+    }
+}
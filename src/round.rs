@@ -0,0 +1,144 @@
+use crate::deal::Deal;
+use crate::game::{Action, DominoSet, DrawRule, GameState, Variant};
+use crate::strategy::Observation;
+
+/// How a round ended.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoundEnd {
+    /// The seat played its last domino.
+    Out(usize),
+    /// The boneyard is empty and nobody can play.
+    Blocked,
+}
+
+/// A round where every hand is known, e.g. a simulation. Enforces whose turn it is and when
+/// a seat may draw or pass; placement rules are enforced by `GameState::play`.
+#[derive(Clone)]
+pub struct Round {
+    /// Shared table, including whose turn it is. `hand` is unused; see `hands`.
+    pub state: GameState,
+    pub hands: Vec<DominoSet>,
+    /// Face-down dominoes. Draws take from the back.
+    pub boneyard: Vec<(u8, u8)>,
+}
+
+impl Round {
+    /// Start a round from `deal`, opening with the `starting` double. If nobody holds it,
+    /// seats draw in turn until someone does. The seat after the one that played it moves
+    /// first.
+    pub fn new(deal: Deal, starting: u8, draw_rule: DrawRule, variant: Variant) -> Self {
+        let Deal {
+            mut hands,
+            mut boneyard,
+            ..
+        } = deal;
+        let players = hands.len();
+        let mut starter = hands.iter().position(|hand| hand.has(starting, starting));
+        let mut seat = 0;
+        while starter.is_none() {
+            let Some((min, max)) = boneyard.pop() else {
+                break;
+            };
+            hands[seat].add(min, max);
+            if (min, max) == (starting, starting) {
+                starter = Some(seat);
+            }
+            seat = (seat + 1) % players;
+        }
+        let starter = starter.expect("the starting double is in a hand or the boneyard");
+        hands[starter].remove(starting, starting);
+
+        let mut state = GameState {
+            players: players as u8,
+            boneyard: boneyard.len() as u8,
+            draw_rule,
+            variant,
+            starting,
+            turn: ((starter + 1) % players) as u8,
+            ..GameState::default()
+        };
+        state.played.add(starting, starting);
+        state.rules().start(&mut state, starter as u8, starting);
+        for (seat, hand) in hands.iter().enumerate() {
+            state.tiles[seat] = hand.len() as u8;
+        }
+        Self {
+            state,
+            hands,
+            boneyard,
+        }
+    }
+
+    /// Seat to move.
+    pub fn turn(&self) -> usize {
+        usize::from(self.state.turn)
+    }
+
+    /// What `seat` can legally know.
+    pub fn observe(&self, seat: usize) -> Observation {
+        let players = self.hands.len();
+        let mut state = self.state.clone();
+        state.hand = self.hands[seat].clone();
+        if seat != self.turn() {
+            state.draws = 0;
+        }
+        Observation {
+            state,
+            opponents: (1..players)
+                .map(|offset| self.hands[(seat + offset) % players].len())
+                .collect(),
+        }
+    }
+
+    /// Perform `action` for the seat to move. Did nothing if `Err` is returned.
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        self.apply_at(action, None)
+    }
+
+    /// `apply`, playing on tile `at` of the board. See `GameState::play_at`.
+    pub fn apply_at(&mut self, action: Action, at: Option<usize>) -> Result<(), String> {
+        let seat = self.turn();
+        match action {
+            Action::Play(mv) => {
+                let (min, max) = mv.domino();
+                if !self.hands[seat].has(min, max) {
+                    return Err(format!("Domino {min} {max} is not in seat {seat}'s hand."));
+                }
+                self.state.record_play_at(mv, at)?;
+                self.hands[seat].remove(min, max);
+            }
+            Action::Draw => {
+                if !self.state.legal_moves(&self.hands[seat]).is_empty() {
+                    return Err("A domino can be played, so drawing is not allowed.".to_owned());
+                }
+                if !self.state.may_draw() {
+                    return Err(if self.boneyard.is_empty() {
+                        "The boneyard is empty.".to_owned()
+                    } else {
+                        "Already drew this turn.".to_owned()
+                    });
+                }
+                let Some((min, max)) = self.boneyard.pop() else {
+                    return Err("The boneyard is empty.".to_owned());
+                };
+                self.hands[seat].add(min, max);
+                self.state.record_draw();
+            }
+            Action::Pass => {
+                if !self.state.legal_moves(&self.hands[seat]).is_empty() {
+                    return Err("A domino can be played, so passing is not allowed.".to_owned());
+                }
+                if !self.state.may_pass() {
+                    return Err("Draw before passing.".to_owned());
+                }
+                self.state.record_pass();
+            }
+        }
+        Ok(())
+    }
+
+    /// `Some` once the round is over. See `Rules::round_end`.
+    pub fn end(&self) -> Option<RoundEnd> {
+        self.state.round_end()
+    }
+}
@@ -0,0 +1,577 @@
+use crate::board::Board;
+use crate::game::{CoverRule, DominoSet, DoubleDomino, GameState, Move, Train};
+use crate::round::RoundEnd;
+use crate::{PIP_MAX_U8, PIP_MAX_USIZE};
+
+/// How the table is shown: a board of ends, or a train per seat.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layout {
+    Endpoints,
+    Trains,
+}
+
+/// What differs between domino games played on `GameState`: setting up the table, which
+/// moves are legal and what they do, when the round is over and how it is scored.
+pub trait Rules {
+    fn layout(&self) -> Layout;
+
+    /// Set up the table once `starter` has put down the first double `pips`. `state.played`
+    /// holds only it.
+    fn start(&self, state: &mut GameState, starter: u8, pips: u8);
+
+    /// Play `mv` for the seat to move on end `at`, or on the first matching end if `None`.
+    /// Did nothing if `Err` is returned.
+    fn play(&self, state: &mut GameState, mv: Move, at: Option<usize>) -> Result<(), String>;
+
+    /// Bit mask of pips the seat to move could play on right now.
+    fn open_pips(&self, state: &GameState) -> u16;
+
+    /// Pips showing at end `at`.
+    fn end_pips(&self, state: &GameState, at: usize) -> Option<u8>;
+
+    /// Whether a double played on a line opens a foot that must be covered.
+    fn opens_feet(&self) -> bool {
+        false
+    }
+
+    /// Whether the seat that played `mv` moves again.
+    fn plays_again(&self, _mv: Move) -> bool {
+        false
+    }
+
+    /// Anything a pass does besides ending the turn.
+    fn pass(&self, _state: &mut GameState) {}
+
+    /// Moves from `dominoes` the seat to move could play.
+    fn legal_moves(&self, state: &GameState, dominoes: &DominoSet) -> Vec<Move> {
+        let mut moves = vec![];
+        for (min, max) in dominoes.as_vector() {
+            let orientations = if min == max {
+                vec![Move {
+                    end: min,
+                    open: max,
+                }]
+            } else {
+                vec![
+                    Move {
+                        end: min,
+                        open: max,
+                    },
+                    Move {
+                        end: max,
+                        open: min,
+                    },
+                ]
+            };
+            for mv in orientations {
+                if self.play(&mut state.clone(), mv, None).is_ok() {
+                    moves.push(mv);
+                }
+            }
+        }
+        moves
+    }
+
+    /// `Some` once a seat is out of dominoes, or once the boneyard is empty and nobody can
+    /// play: every seat passed in a row, or no unplayed domino fits anywhere.
+    fn round_end(&self, state: &GameState) -> Option<RoundEnd> {
+        if let Some(seat) = (0..usize::from(state.players)).find(|&seat| state.tiles[seat] == 0) {
+            return Some(RoundEnd::Out(seat));
+        }
+        let unplayed = state.played.inverted();
+        let stuck = |seat| {
+            let mut state = state.clone();
+            state.turn = seat;
+            self.legal_moves(&state, &unplayed).is_empty()
+        };
+        if state.boneyard == 0 && (state.passes >= state.players || (0..state.players).all(stuck)) {
+            return Some(RoundEnd::Blocked);
+        }
+        None
+    }
+
+    /// Pips showing at the ends, in games that score them during play.
+    fn end_sum(&self, _state: &GameState) -> Option<u32> {
+        None
+    }
+
+    /// Points each seat scores for the round given the pips `leftover` in each hand. Lowest
+    /// total wins.
+    fn score(&self, _state: &GameState, leftover: &[u32]) -> Vec<u32> {
+        leftover.to_vec()
+    }
+}
+
+/// Lines and chickenfeet out of the first double.
+pub struct Chickenfoot;
+
+/// A train per seat out of the hub double, plus the public Mexican train.
+pub struct MexicanTrain;
+
+/// All Fives, or Muggins: lines out of a spinner. Whenever the ends add up to a multiple of
+/// five, the seat that played scores the sum. Highest total wins.
+pub struct AllFives;
+
+impl AllFives {
+    /// Points scored when the ends add up to `sum`.
+    pub fn points(sum: u32) -> u32 {
+        if sum.is_multiple_of(5) {
+            sum
+        } else {
+            0
+        }
+    }
+}
+
+impl Chickenfoot {
+    /// Play a domino on the endpoint counts, `min` half first if `min_matches`. `on_double`
+    /// says whether it covers the double, as known from the board; if `None`, any domino
+    /// matching the double covers it.
+    fn count_play(
+        &self,
+        state: &mut GameState,
+        min: u8,
+        max: u8,
+        min_matches: bool,
+        on_double: Option<bool>,
+    ) -> Result<(), String> {
+        #[cfg(debug_assertions)]
+        if state.played.has(min, max) {
+            return Err(format!("Domino {min} {max} has already been played."));
+        }
+
+        // Rotate domino.
+        let (previous_endpoint, next_endpoint) = if min_matches {
+            (min as usize, max as usize)
+        } else {
+            (max as usize, min as usize)
+        };
+
+        // Play on the double being covered.
+        if let Some(double_domino) = state.doubles.first_mut() {
+            debug_assert!(double_domino.count < 4);
+            if on_double.unwrap_or(usize::from(double_domino.pips) == previous_endpoint) {
+                state.endpoints[next_endpoint] += 1;
+                double_domino.count += 1;
+                if double_domino.count >= double_domino.max_count() {
+                    state.doubles.remove(0);
+                }
+                state.played.add(min, max);
+                return Ok(());
+            }
+            if state.cover_rule == CoverRule::Immediately {
+                return Err(format!(
+                    "Invalid Move: play a {} on the double domino.",
+                    double_domino.pips
+                ));
+            }
+        }
+
+        // Play a double domino. It waits behind any doubles still uncovered.
+        if min == max {
+            if state.endpoints[previous_endpoint] == 0 {
+                return Err("Invalid Move: play on an end.".to_owned());
+            }
+            state.endpoints[previous_endpoint] -= 1;
+            state.doubles.push(DoubleDomino {
+                pips: min,
+                count: 0,
+                first: false,
+            });
+            state.played.add(min, max);
+            return Ok(());
+        }
+
+        // Play a non-double domino on another non-double domino.
+        if state.endpoints[previous_endpoint] == 0 {
+            return Err("Invalid Move: play on an end.".to_owned());
+        }
+        state.endpoints[previous_endpoint] -= 1;
+        state.endpoints[next_endpoint] += 1;
+        state.played.add(min, max);
+        Ok(())
+    }
+}
+
+impl Rules for Chickenfoot {
+    fn layout(&self) -> Layout {
+        Layout::Endpoints
+    }
+
+    fn opens_feet(&self) -> bool {
+        true
+    }
+
+    fn start(&self, state: &mut GameState, _starter: u8, pips: u8) {
+        state.doubles = vec![DoubleDomino {
+            pips,
+            count: 0,
+            first: true,
+        }];
+        state.endpoints = [0; PIP_MAX_USIZE + 1];
+        state.board = Some(Board::new(pips));
+    }
+
+    /// `at` is a tile of the board. Endpoint counts are derived from the board while it is
+    /// known.
+    fn play(&self, state: &mut GameState, mv: Move, at: Option<usize>) -> Result<(), String> {
+        let mut board = state
+            .board
+            .clone()
+            .filter(|board| board.len() == state.played.len());
+        let mut on_double = None;
+        if let Some(board) = &mut board {
+            let at = match at {
+                Some(at) => at,
+                None => board
+                    .find_end(mv.end, state.cover_rule)
+                    .ok_or("Invalid Move: play on an end.")?,
+            };
+            on_double = Some(board.pending_doubles().first() == Some(&at));
+            board.attach(mv.end, mv.open, at, state.cover_rule)?;
+        }
+        let (min, max) = mv.domino();
+        self.count_play(state, min, max, mv.end == min, on_double)?;
+        if let Some(board) = &board {
+            state.endpoints = board.endpoints();
+        }
+        state.board = board;
+        Ok(())
+    }
+
+    /// The double to cover, and every end unless `cover_rule` makes the double come first.
+    fn open_pips(&self, state: &GameState) -> u16 {
+        let double = state.double().map_or(0, |double| 1 << double.pips);
+        if double != 0 && state.cover_rule == CoverRule::Immediately {
+            return double;
+        }
+        (0..=PIP_MAX_U8)
+            .filter(|&pips| state.endpoints[usize::from(pips)] > 0)
+            .fold(double, |mask, pips| mask | 1 << pips)
+    }
+
+    fn end_pips(&self, state: &GameState, at: usize) -> Option<u8> {
+        Some(state.board.as_ref()?.tiles.get(at)?.open)
+    }
+}
+
+impl Rules for MexicanTrain {
+    fn layout(&self) -> Layout {
+        Layout::Trains
+    }
+
+    /// The hub takes no toes; every train starts from it.
+    fn start(&self, state: &mut GameState, _starter: u8, pips: u8) {
+        state.doubles.clear();
+        state.board = None;
+        state.trains = vec![
+            Train {
+                end: pips,
+                ..Train::default()
+            };
+            usize::from(state.players) + 1
+        ];
+        state.endpoints = [0; PIP_MAX_USIZE + 1];
+        state.endpoints[usize::from(pips)] = state.players + 1;
+    }
+
+    /// `at` is a train. A double left uncovered takes the next domino, whoever's train it is
+    /// on.
+    fn play(&self, state: &mut GameState, mv: Move, at: Option<usize>) -> Result<(), String> {
+        let (min, max) = mv.domino();
+        #[cfg(debug_assertions)]
+        if state.played.has(min, max) {
+            return Err(format!("Domino {min} {max} has already been played."));
+        }
+        let seat = state.turn;
+        let uncovered = state.trains.iter().position(|train| train.double);
+        let at = match (uncovered, at) {
+            (Some(double), Some(at)) if at != double => {
+                let pips = state.trains[double].end;
+                return Err(format!(
+                    "Invalid Move: cover the {pips} {pips} double first."
+                ));
+            }
+            (Some(double), _) => double,
+            (None, Some(at)) => at,
+            (None, None) => (0..state.trains.len())
+                .filter(|&train| state.train_open(seat, train))
+                .find(|&train| state.trains[train].end == mv.end)
+                .ok_or(format!("Invalid Move: no open train ends in a {}.", mv.end))?,
+        };
+        let Some(train) = state.trains.get(at) else {
+            return Err("Invalid Move: no such train.".to_owned());
+        };
+        if uncovered != Some(at) && !state.train_open(seat, at) {
+            return Err("Invalid Move: that train is not open to you.".to_owned());
+        }
+        if train.end != mv.end {
+            return Err(if uncovered == Some(at) {
+                format!("Invalid Move: cover the {0} {0} double first.", train.end)
+            } else {
+                format!("Invalid Move: play a {} on that train.", train.end)
+            });
+        }
+        let endpoint = &mut state.endpoints[usize::from(mv.end)];
+        *endpoint = endpoint.saturating_sub(1);
+        state.endpoints[usize::from(mv.open)] += 1;
+        let train = &mut state.trains[at];
+        train.end = mv.open;
+        train.double = min == max;
+        if at == usize::from(seat) {
+            train.marker = false;
+        }
+        state.played.add(min, max);
+        Ok(())
+    }
+
+    /// The uncovered double, or the trains open to the seat.
+    fn open_pips(&self, state: &GameState) -> u16 {
+        if let Some(train) = state.trains.iter().find(|train| train.double) {
+            return 1 << train.end;
+        }
+        (0..state.trains.len())
+            .filter(|&train| state.train_open(state.turn, train))
+            .fold(0, |mask, train| mask | 1 << state.trains[train].end)
+    }
+
+    fn end_pips(&self, state: &GameState, at: usize) -> Option<u8> {
+        Some(state.trains.get(at)?.end)
+    }
+
+    /// Play again to cover the double.
+    fn plays_again(&self, mv: Move) -> bool {
+        mv.end == mv.open
+    }
+
+    /// A seat that cannot play opens its train to everyone.
+    fn pass(&self, state: &mut GameState) {
+        if let Some(train) = state.trains.get_mut(usize::from(state.turn)) {
+            train.marker = true;
+        }
+    }
+}
+
+impl Rules for AllFives {
+    fn layout(&self) -> Layout {
+        Layout::Endpoints
+    }
+
+    /// Leading a double that is a multiple of five already scores.
+    fn start(&self, state: &mut GameState, starter: u8, pips: u8) {
+        let board = Board::spinner(pips);
+        state.doubles.clear();
+        state.endpoints = board.endpoints();
+        state.scores[usize::from(starter)] += Self::points(board.end_sum());
+        state.board = Some(board);
+    }
+
+    /// `at` is a tile of the board. Nothing is scored once the layout is unknown.
+    fn play(&self, state: &mut GameState, mv: Move, at: Option<usize>) -> Result<(), String> {
+        let (min, max) = mv.domino();
+        #[cfg(debug_assertions)]
+        if state.played.has(min, max) {
+            return Err(format!("Domino {min} {max} has already been played."));
+        }
+        let mut board = state
+            .board
+            .clone()
+            .filter(|board| board.len() == state.played.len());
+        if let Some(board) = &mut board {
+            let at = match at {
+                Some(at) => at,
+                None => board
+                    .find_end(mv.end, state.cover_rule)
+                    .ok_or("Invalid Move: play on an end.")?,
+            };
+            board.attach(mv.end, mv.open, at, state.cover_rule)?;
+            state.endpoints = board.endpoints();
+            state.scores[usize::from(state.turn)] += Self::points(board.end_sum());
+        } else {
+            let endpoint = &mut state.endpoints[usize::from(mv.end)];
+            if *endpoint == 0 {
+                return Err("Invalid Move: play on an end.".to_owned());
+            }
+            *endpoint -= 1;
+            state.endpoints[usize::from(mv.open)] += 1;
+        }
+        state.board = board;
+        state.played.add(min, max);
+        Ok(())
+    }
+
+    fn open_pips(&self, state: &GameState) -> u16 {
+        (0..=PIP_MAX_U8)
+            .filter(|&pips| state.endpoints[usize::from(pips)] > 0)
+            .fold(0, |mask, pips| mask | 1 << pips)
+    }
+
+    fn end_pips(&self, state: &GameState, at: usize) -> Option<u8> {
+        Some(state.board.as_ref()?.tiles.get(at)?.open)
+    }
+
+    fn end_sum(&self, state: &GameState) -> Option<u32> {
+        let board = state.board.as_ref()?;
+        Some(board.end_sum())
+    }
+
+    /// The seat with the fewest pips left takes everyone else's, less its own, rounded to
+    /// the nearest five.
+    fn score(&self, _state: &GameState, leftover: &[u32]) -> Vec<u32> {
+        let mut points = vec![0; leftover.len()];
+        let Some(winner) = (0..leftover.len()).min_by_key(|&seat| leftover[seat]) else {
+            return points;
+        };
+        let others: u32 = leftover.iter().sum::<u32>() - leftover[winner];
+        let total = others.saturating_sub(leftover[winner]);
+        points[winner] = (total + 2) / 5 * 5;
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllFives, Rules};
+    use crate::game::{CoverRule, DominoSet, GameState, Move, Variant};
+
+    /// Three seats of Mexican Train after seat 0 set down the double twelve hub.
+    fn mexican_train() -> GameState {
+        let mut state = GameState {
+            variant: Variant::MexicanTrain,
+            players: 3,
+            ..GameState::default()
+        };
+        state.doubles[0].pips = 12;
+        state.hand.add(12, 12);
+        state.start_round(0).unwrap();
+        state
+    }
+
+    #[test]
+    fn in_order_play_beside_a_pending_double_leaves_it_pending() {
+        let mut state = GameState {
+            cover_rule: CoverRule::InOrder,
+            ..GameState::default()
+        };
+        state.doubles[0].pips = 12;
+        state.hand.add(12, 12);
+        state.start_round(0).unwrap();
+        for (end, open) in [(12, 5), (12, 1), (12, 2), (12, 3), (1, 5), (5, 5)] {
+            state.play_move(Move { end, open }).unwrap();
+        }
+        let board = state.board.clone().unwrap();
+        let line_end = board
+            .open_ends(CoverRule::InOrder)
+            .into_iter()
+            .find(|&index| !board.tiles[index].is_double() && board.tiles[index].open == 5)
+            .unwrap();
+        state
+            .play_at(Move { end: 5, open: 7 }, Some(line_end))
+            .unwrap();
+        let board = state.board.as_ref().unwrap();
+        let pending = board.pending_doubles();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(board.tiles[pending[0]].children, 0);
+        let double = state.double().unwrap();
+        assert_eq!((double.pips, double.count), (5, 0));
+        assert_eq!(state.endpoints, board.endpoints());
+    }
+
+    #[test]
+    fn trains_open_with_a_marker() {
+        let mut state = mexican_train();
+        assert_eq!(state.turn, 1);
+        assert!(state
+            .clone()
+            .play_at(Move { end: 12, open: 4 }, Some(2))
+            .is_err());
+        assert!(state
+            .clone()
+            .play_at(Move { end: 12, open: 4 }, Some(3))
+            .is_ok());
+        state
+            .record_play_at(Move { end: 12, open: 4 }, Some(1))
+            .unwrap();
+        state.record_pass();
+        assert!(state.trains[2].marker);
+        assert_eq!(state.turn, 0);
+        assert!(state
+            .clone()
+            .play_at(Move { end: 12, open: 6 }, Some(2))
+            .is_ok());
+        state.record_pass();
+        state
+            .record_play_at(Move { end: 4, open: 5 }, Some(1))
+            .unwrap();
+        state
+            .record_play_at(Move { end: 12, open: 7 }, Some(2))
+            .unwrap();
+        assert!(!state.trains[2].marker);
+        assert!(state.trains[0].marker);
+    }
+
+    #[test]
+    fn uncovered_double_takes_the_next_play() {
+        let mut state = mexican_train();
+        state
+            .record_play_at(Move { end: 12, open: 4 }, Some(1))
+            .unwrap();
+        state
+            .record_play_at(Move { end: 12, open: 5 }, Some(2))
+            .unwrap();
+        state
+            .record_play_at(Move { end: 12, open: 9 }, Some(0))
+            .unwrap();
+        state
+            .record_play_at(Move { end: 4, open: 4 }, Some(1))
+            .unwrap();
+        assert_eq!(state.turn, 1);
+        assert_eq!(state.open_pips(), 1 << 4);
+        let mut dominoes = DominoSet::default();
+        for (min, max) in [(4, 6), (5, 6), (9, 10), (3, 12)] {
+            dominoes.add(min, max);
+        }
+        assert_eq!(state.legal_moves(&dominoes), vec![Move { end: 4, open: 6 }]);
+        assert!(state
+            .clone()
+            .play_at(Move { end: 12, open: 3 }, Some(3))
+            .is_err());
+        state.record_play(Move { end: 4, open: 6 }).unwrap();
+        assert_eq!(state.trains[1].end, 6);
+        assert!(!state.trains[1].double);
+        assert_eq!(state.turn, 2);
+    }
+
+    #[test]
+    fn all_fives_counts_the_spinner_until_two_sides_are_played() {
+        let mut state = GameState {
+            variant: Variant::AllFives,
+            players: 3,
+            ..GameState::default()
+        };
+        state.doubles[0].pips = 5;
+        state.hand.add(5, 5);
+        state.start_round(0).unwrap();
+        assert_eq!(AllFives.end_sum(&state), Some(10));
+        assert_eq!(state.scores[0], 10);
+        // One side: the spinner still counts 10.
+        state.record_play(Move { end: 5, open: 3 }).unwrap();
+        assert_eq!(AllFives.end_sum(&state), Some(13));
+        assert_eq!(state.scores[1], 0);
+        // Both sides: only the line ends count.
+        state.record_play(Move { end: 5, open: 1 }).unwrap();
+        assert_eq!(AllFives.end_sum(&state), Some(4));
+        // A double at the end of a line counts both halves.
+        state.record_play(Move { end: 1, open: 1 }).unwrap();
+        assert_eq!(AllFives.end_sum(&state), Some(5));
+        assert_eq!(state.scores[..3], [15, 0, 0]);
+    }
+
+    #[test]
+    fn all_fives_round_score_rounds_to_the_nearest_five() {
+        let state = GameState::default();
+        assert_eq!(AllFives.score(&state, &[3, 20, 12]), vec![30, 0, 0]);
+        assert_eq!(AllFives.score(&state, &[12, 0]), vec![0, 10]);
+        assert_eq!(AllFives.score(&state, &[0, 13]), vec![15, 0]);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::game::{Block, GameState, Move};
+use crate::PIP_MAX_U8;
+
+/// Weight of pips shed one play further down a line.
+const DISCOUNT: f32 = 0.9;
+/// Value of each domino in hand that can still be played at the end of a line.
+const MOBILITY: f32 = 0.5;
+/// Damage control, once an opponent may go out next turn: weight of pips shed one play
+/// further down a line, since later plays may never happen.
+const DANGER_DISCOUNT: f32 = 0.2;
+/// Damage control: value of a play that leaves no opponent anything to play on, and cost of
+/// one that opens an end only opponents can use.
+const BLOCK: f32 = 10.0;
+/// Positions `plan` looks at before settling for the best plans found so far.
+const PLAN_NODES: u64 = 5_000;
+/// Partnerships: value of an open pip a partner may hold, and cost of one an opponent may.
+const TEAM: f32 = 1.0;
+
+pub struct SearchResult {
+    pub score: f32,
+    /// Principal variation: the best line of the seat's own plays, best move first.
+    pub pv: Vec<Move>,
+    /// Number of positions evaluated.
+    pub nodes: u64,
+}
+
+/// Search sequences of up to `depth` plays from `state.hand` by the seat to move, as if the
+/// opponents never block an end. Lines are scored by the pips they shed, earlier plays weighted more. With
+/// teams, each play also scores the ends it leaves for partners and opponents. Once an
+/// opponent is down to one or two dominoes, the search switches to damage control: shed
+/// heavy dominoes now and block.
+/// Returns `None` if nothing in the hand can be played.
+pub fn search(state: &GameState, depth: u32) -> Option<SearchResult> {
+    let mut nodes = 0;
+    let (score, pv) = search_line(state, depth.max(1), &mut nodes);
+    if pv.is_empty() {
+        None
+    } else {
+        Some(SearchResult { score, pv, nodes })
+    }
+}
+
+/// Plans for the user's own plays, as if the opponents never block an end.
+#[derive(Clone, Default)]
+pub struct Plan {
+    /// Most dominoes played in a row. Doubles count with the toes the hand can put on them.
+    pub longest: Vec<Move>,
+    /// Order that sheds the most pips earliest, weighted like `search`.
+    pub shedding: Vec<Move>,
+}
+
+/// Find the longest chain of plays through the user's hand, and the order that sheds pips
+/// fastest. Gives up searching after `PLAN_NODES` positions and returns the best so far.
+pub fn plan(state: &GameState) -> Plan {
+    let mut planner = Planner {
+        line: vec![],
+        plan: Plan::default(),
+        best_shed: 0.0,
+        nodes: 0,
+    };
+    planner.extend(state, 0.0, 1.0);
+    planner.plan
+}
+
+struct Planner {
+    line: Vec<Move>,
+    plan: Plan,
+    best_shed: f32,
+    nodes: u64,
+}
+
+impl Planner {
+    /// Try every play from `state`, `shed` being the weighted pips of `line` so far.
+    fn extend(&mut self, state: &GameState, shed: f32, weight: f32) {
+        self.nodes += 1;
+        if self.line.len() > self.plan.longest.len() {
+            self.plan.longest = self.line.clone();
+        }
+        if shed > self.best_shed {
+            self.best_shed = shed;
+            self.plan.shedding = self.line.clone();
+        }
+        let mut moves = state.legal_moves(&state.hand);
+        // Heavy dominoes first, so the shedding plan is good even if the search is cut short.
+        moves.sort_by_key(|mv| std::cmp::Reverse(mv.pips()));
+        for mv in moves {
+            if self.nodes >= PLAN_NODES {
+                return;
+            }
+            let mut child = state.clone();
+            if child.play_move(mv).is_err() {
+                continue;
+            }
+            let (min, max) = mv.domino();
+            child.hand.remove(min, max);
+            self.line.push(mv);
+            self.extend(&child, shed + weight * mv.pips() as f32, weight * DISCOUNT);
+            self.line.pop();
+        }
+    }
+}
+
+fn search_line(state: &GameState, depth: u32, nodes: &mut u64) -> (f32, Vec<Move>) {
+    *nodes += 1;
+    let moves = state.legal_moves(&state.hand);
+    if depth == 0 || moves.is_empty() {
+        return (MOBILITY * moves.len() as f32, vec![]);
+    }
+    let mut best = (f32::MIN, vec![]);
+    for mv in moves {
+        let mut child = state.clone();
+        if child.play_move(mv).is_err() {
+            continue;
+        }
+        let (min, max) = mv.domino();
+        child.hand.remove(min, max);
+        let (score, mut pv) = search_line(&child, depth - 1, nodes);
+        let score = if state.danger(state.turn).is_empty() {
+            mv.pips() as f32 + TEAM * team_ends(&child) + DISCOUNT * score
+        } else {
+            let block = match state.block(state.turn, mv) {
+                Some(Block::LockOut) => BLOCK,
+                Some(Block::OpensForOpponents) => -BLOCK,
+                _ => 0.0,
+            };
+            mv.pips() as f32 + block + TEAM * team_ends(&child) + DANGER_DISCOUNT * score
+        };
+        if score > best.0 {
+            pv.insert(0, mv);
+            best = (score, pv);
+        }
+    }
+    best
+}
+
+/// How much the team of the seat to move stands to gain from the pips open after a play: for each open
+/// pip, the share of partners that may hold it less the share of opponents that may, going
+/// by the pips each seat showed it lacks when it passed. Zero without teams.
+fn team_ends(state: &GameState) -> f32 {
+    if !state.has_teams() {
+        return 0.0;
+    }
+    let unseen = state.unseen();
+    let open = state.open_pips();
+    let share = |seats: &[u8], pips: u8| {
+        if seats.is_empty() {
+            return 0.0;
+        }
+        let may_hold = seats
+            .iter()
+            .filter(|&&seat| state.excluded[usize::from(seat)] & 1 << pips == 0)
+            .count();
+        may_hold as f32 / seats.len() as f32
+    };
+    let (partners, opponents): (Vec<u8>, Vec<u8>) = (0..state.players)
+        .filter(|&seat| seat != state.turn)
+        .partition(|&seat| state.partners(state.turn, seat));
+    (0..=PIP_MAX_U8)
+        .filter(|&pips| open & 1 << pips != 0 && unseen.count_pips(pips) > 0)
+        .map(|pips| share(&partners, pips) - share(&opponents, pips))
+        .sum()
+}
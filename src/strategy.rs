@@ -0,0 +1,318 @@
+use crate::game::{Action, DominoSet, GameState, Move};
+use crate::rng::Rng;
+use crate::round::{Round, RoundEnd};
+use crate::search::search;
+
+/// Everything a seat can legally know on its turn.
+#[derive(Clone)]
+pub struct Observation {
+    /// The table with `hand` set to the seat's own hand and `boneyard` to its size.
+    pub state: GameState,
+    /// Number of dominoes held by each other seat, in turn order starting with the next seat.
+    pub opponents: Vec<usize>,
+}
+
+impl Observation {
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.state.legal_moves(&self.state.hand)
+    }
+
+    /// Dominoes that are neither played nor in the seat's hand.
+    pub fn unseen(&self) -> DominoSet {
+        self.state.unseen()
+    }
+
+    /// What to do when nothing can be played: draw if allowed, otherwise pass.
+    pub fn stuck_action(&self) -> Action {
+        if self.state.may_draw() {
+            Action::Draw
+        } else {
+            Action::Pass
+        }
+    }
+}
+
+/// A way of choosing what a seat does on its turn.
+pub trait Strategy {
+    fn name(&self) -> &str;
+    fn choose(&mut self, observation: &Observation) -> Action;
+}
+
+/// Names accepted by `strategy_by_name`.
+pub const STRATEGY_NAMES: [&str; 5] = [
+    "random",
+    "greedy",
+    "double-hoarder",
+    "monte-carlo",
+    "search",
+];
+
+/// Construct one of the built-in strategies.
+pub fn strategy_by_name(name: &str, rng: Rng) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(RandomStrategy::new(rng))),
+        "greedy" => Some(Box::new(GreedyStrategy)),
+        "double-hoarder" => Some(Box::new(DoubleHoarderStrategy)),
+        "monte-carlo" => Some(Box::new(MonteCarloStrategy::new(rng, 50))),
+        "search" => Some(Box::new(SearchStrategy { depth: 3 })),
+        _ => None,
+    }
+}
+
+/// Plays a uniformly random legal move.
+pub struct RandomStrategy {
+    rng: Rng,
+}
+
+impl RandomStrategy {
+    pub fn new(rng: Rng) -> Self {
+        Self { rng }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Action {
+        let moves = observation.legal_moves();
+        if moves.is_empty() {
+            observation.stuck_action()
+        } else {
+            Action::Play(moves[self.rng.below(moves.len())])
+        }
+    }
+}
+
+/// Sheds the heaviest playable domino first.
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Action {
+        observation
+            .legal_moves()
+            .into_iter()
+            .max_by_key(Move::pips)
+            .map_or_else(|| observation.stuck_action(), Action::Play)
+    }
+}
+
+/// Holds on to doubles, which force every seat to cover them, until nothing else can be
+/// played. Otherwise plays heaviest first.
+pub struct DoubleHoarderStrategy;
+
+impl Strategy for DoubleHoarderStrategy {
+    fn name(&self) -> &str {
+        "double-hoarder"
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Action {
+        observation
+            .legal_moves()
+            .into_iter()
+            .max_by_key(|mv| (mv.end != mv.open, mv.pips()))
+            .map_or_else(|| observation.stuck_action(), Action::Play)
+    }
+}
+
+/// Plays the first move of the best line found by `search`, as the built-in engine does.
+pub struct SearchStrategy {
+    pub depth: u32,
+}
+
+impl Strategy for SearchStrategy {
+    fn name(&self) -> &str {
+        "search"
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Action {
+        match search(&observation.state, self.depth) {
+            Some(result) => Action::Play(result.pv[0]),
+            None => observation.stuck_action(),
+        }
+    }
+}
+
+/// Tries each legal move against random deals of the unseen dominoes, played out with
+/// `GreedyStrategy`, and picks the move that goes out most often.
+pub struct MonteCarloStrategy {
+    rng: Rng,
+    /// Rollouts per legal move.
+    pub samples: usize,
+}
+
+impl MonteCarloStrategy {
+    /// Upper bound on turns in a rollout, in case the players cycle.
+    const MAX_TURNS: usize = 1000;
+
+    pub fn new(rng: Rng, samples: usize) -> Self {
+        Self { rng, samples }
+    }
+
+    /// Random round consistent with `observation`, with the observing seat to move. Seats
+    /// that passed are dealt no dominoes with pips they showed they lack, when there are
+    /// enough others.
+    fn determinize(&mut self, observation: &Observation) -> Round {
+        let state = &observation.state;
+        let seat = usize::from(state.turn);
+        let players = observation.opponents.len() + 1;
+        let mut unseen = observation.unseen().as_vector();
+        self.rng.shuffle(&mut unseen);
+        let mut hands = vec![DominoSet::default(); players];
+        hands[seat] = state.hand.clone();
+        // Deal to the seats known to lack the most pips first, while there is a choice.
+        let mut opponents: Vec<(usize, usize)> = observation
+            .opponents
+            .iter()
+            .enumerate()
+            .map(|(offset, &count)| ((seat + 1 + offset) % players, count))
+            .collect();
+        opponents.sort_by_key(|&(other, _)| std::cmp::Reverse(state.excluded[other].count_ones()));
+        for (other, count) in opponents {
+            let lacks = state.excluded[other];
+            let mut hand = DominoSet::default();
+            for strict in [true, false] {
+                unseen.retain(|&(min, max)| {
+                    let fits = !strict || lacks & (1 << min | 1 << max) == 0;
+                    if fits && hand.len() < count {
+                        hand.add(min, max);
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+            hands[other] = hand;
+        }
+        unseen.truncate(usize::from(state.boneyard));
+        let mut state = state.clone();
+        state.hand.clear();
+        for (seat, hand) in hands.iter().enumerate() {
+            state.tiles[seat] = hand.len() as u8;
+        }
+        Round {
+            state,
+            hands,
+            boneyard: unseen,
+        }
+    }
+
+    /// 1 if `seat` goes out, 0 if another seat does. Blocked rounds are won by the lightest
+    /// hand.
+    fn rollout(mut round: Round, seat: usize) -> f32 {
+        let mut greedy = GreedyStrategy;
+        for _ in 0..Self::MAX_TURNS {
+            match round.end() {
+                Some(RoundEnd::Out(out)) => return if out == seat { 1.0 } else { 0.0 },
+                Some(RoundEnd::Blocked) => break,
+                None => {}
+            }
+            let action = greedy.choose(&round.observe(round.turn()));
+            if round.apply(action).is_err() {
+                break;
+            }
+        }
+        let own = round.hands[seat].pips();
+        if round.hands.iter().all(|hand| own <= hand.pips()) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Strategy for MonteCarloStrategy {
+    fn name(&self) -> &str {
+        "monte-carlo"
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Action {
+        let moves = observation.legal_moves();
+        if moves.len() <= 1 {
+            return moves
+                .first()
+                .map_or_else(|| observation.stuck_action(), |&mv| Action::Play(mv));
+        }
+        let mut best = (f32::MIN, moves[0]);
+        for mv in moves {
+            let mut wins = 0.0;
+            for _ in 0..self.samples {
+                let mut round = self.determinize(observation);
+                if round.apply(Action::Play(mv)).is_ok() {
+                    wins += Self::rollout(round, usize::from(observation.state.turn));
+                }
+            }
+            // Break ties toward shedding more pips.
+            let score = wins + mv.pips() as f32 * 1e-3;
+            if score > best.0 {
+                best = (score, mv);
+            }
+        }
+        Action::Play(best.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seat 2 of 3 with a light chain 3-4, 4-10, 10-11 and a heavy 8-9 nobody else can
+    /// follow. `tiles` gives each seat's domino count.
+    fn observation(tiles: [u8; 3]) -> Observation {
+        let mut state = GameState {
+            doubles: vec![],
+            players: 3,
+            boneyard: 0,
+            turn: 2,
+            ..GameState::default()
+        };
+        state.endpoints[3] = 1;
+        state.endpoints[9] = 1;
+        state.played.add(3, 12);
+        state.played.add(9, 12);
+        state.played.add(12, 12);
+        for (min, max) in [(3, 4), (4, 10), (10, 11), (8, 9)] {
+            state.hand.add(min, max);
+        }
+        state.tiles[..3].copy_from_slice(&tiles);
+        state.excluded[0] = 1 << 8;
+        state.excluded[1] = 1 << 8;
+        Observation {
+            state,
+            opponents: vec![usize::from(tiles[0]), usize::from(tiles[1])],
+        }
+    }
+
+    #[test]
+    fn search_strategy_reacts_to_seat_0_going_out() {
+        let mut strategy = SearchStrategy { depth: 3 };
+        let calm = Move { end: 3, open: 4 };
+        let dump = Move { end: 9, open: 8 };
+        // Its own hand running low is no reason to panic.
+        assert_eq!(strategy.choose(&observation([5, 5, 2])), Action::Play(calm));
+        assert_eq!(strategy.choose(&observation([1, 5, 4])), Action::Play(dump));
+    }
+
+    #[test]
+    fn sampled_hands_keep_to_what_passes_showed() {
+        let observation = observation([5, 5, 2]);
+        let mut strategy = MonteCarloStrategy::new(Rng::new(1), 1);
+        for _ in 0..100 {
+            let round = strategy.determinize(&observation);
+            assert_eq!(round.turn(), 2);
+            assert_eq!(
+                round.hands[2].as_vector(),
+                observation.state.hand.as_vector()
+            );
+            for seat in 0..2 {
+                assert_eq!(round.hands[seat].len(), 5);
+                assert_eq!(round.hands[seat].count_pips(8), 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::deal::Deal;
+use crate::game::{Action, DrawRule, Variant};
+use crate::rng::Rng;
+use crate::round::{Round, RoundEnd};
+use crate::strategy::{strategy_by_name, Strategy};
+use crate::PIP_MAX_U8;
+
+/// Upper bound on actions in a game, in case the players cycle. Treated as blocked.
+const MAX_ACTIONS: usize = 10_000;
+/// Two-sided 95% normal quantile.
+const Z: f64 = 1.96;
+
+/// Results of one strategy over a tournament.
+#[derive(Clone, Default)]
+pub struct Standing {
+    pub name: String,
+    pub games: u32,
+    /// Games won. A blocked game is won by the lightest hand; ties share the win.
+    pub wins: f64,
+    pub pips: u64,
+    pub pips_squared: u64,
+}
+
+impl Standing {
+    pub fn win_rate(&self) -> f64 {
+        self.wins / f64::from(self.games.max(1))
+    }
+
+    /// 95% Wilson score interval of the win rate.
+    pub fn win_rate_interval(&self) -> (f64, f64) {
+        let n = f64::from(self.games.max(1));
+        let p = self.win_rate();
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let half = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+        (center - half, center + half)
+    }
+
+    /// Mean pips left in hand at the end of a game.
+    pub fn mean_pips(&self) -> f64 {
+        self.pips as f64 / f64::from(self.games.max(1))
+    }
+
+    /// Half-width of the 95% normal confidence interval of `mean_pips`.
+    pub fn mean_pips_margin(&self) -> f64 {
+        let n = f64::from(self.games.max(1));
+        let mean = self.mean_pips();
+        let variance = (self.pips_squared as f64 / n - mean * mean).max(0.0);
+        Z * (variance / n).sqrt()
+    }
+}
+
+pub struct TournamentResult {
+    pub standings: Vec<Standing>,
+    /// `head_to_head[a][b]`: games in which strategy `a` finished with fewer pips than `b`.
+    pub head_to_head: Vec<Vec<u32>>,
+    pub games: u32,
+    pub blocked: u32,
+}
+
+/// Play `round` to the end with `strategies[seat]` choosing for each seat.
+pub fn play_out(round: &mut Round, strategies: &mut [Box<dyn Strategy>]) -> RoundEnd {
+    for _ in 0..MAX_ACTIONS {
+        if let Some(end) = round.end() {
+            return end;
+        }
+        let seat = round.turn();
+        let observation = round.observe(seat);
+        let action = strategies[seat].choose(&observation);
+        if round.apply(action).is_err() {
+            // Illegal choice: do the least the seat must do instead.
+            let fallback = observation.stuck_action();
+            if round.apply(fallback).is_err() {
+                let mv = observation.legal_moves()[0];
+                round.apply(Action::Play(mv)).expect("legal move");
+            }
+        }
+    }
+    RoundEnd::Blocked
+}
+
+/// Play `games` games of double-twelve `variant` between the named strategies, one seat each. Game `i`
+/// is dealt from seed `seed + i`, and seats rotate every game so no strategy always starts
+/// in the same position.
+pub fn run(
+    names: &[String],
+    games: u32,
+    seed: u64,
+    draw_rule: DrawRule,
+    variant: Variant,
+) -> Result<TournamentResult, String> {
+    let players = names.len();
+    if !(2..=8).contains(&players) {
+        return Err("A tournament needs 2 to 8 strategies.".to_owned());
+    }
+    let mut result = TournamentResult {
+        standings: names
+            .iter()
+            .map(|name| Standing {
+                name: name.clone(),
+                ..Standing::default()
+            })
+            .collect(),
+        head_to_head: vec![vec![0; players]; players],
+        games,
+        blocked: 0,
+    };
+    for game in 0..games {
+        let deal = Deal::new(seed.wrapping_add(u64::from(game)), players);
+        let mut rng = deal.game_rng();
+        // Strategy index for each seat.
+        let seating: Vec<usize> = (0..players)
+            .map(|seat| (seat + game as usize) % players)
+            .collect();
+        let mut strategies = seating
+            .iter()
+            .map(|&index| {
+                strategy_by_name(&names[index], Rng::new(rng.next_u64()))
+                    .ok_or_else(|| format!("Unknown strategy {}.", names[index]))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut round = Round::new(deal, PIP_MAX_U8, draw_rule, variant);
+        let end = play_out(&mut round, &mut strategies);
+
+        let pips: Vec<u32> = round.hands.iter().map(|hand| hand.pips()).collect();
+        let winners: Vec<usize> = match end {
+            RoundEnd::Out(seat) => vec![seat],
+            RoundEnd::Blocked => {
+                result.blocked += 1;
+                let least = *pips.iter().min().expect("at least two seats");
+                (0..players).filter(|&seat| pips[seat] == least).collect()
+            }
+        };
+        for seat in 0..players {
+            let standing = &mut result.standings[seating[seat]];
+            standing.games += 1;
+            standing.pips += u64::from(pips[seat]);
+            standing.pips_squared += u64::from(pips[seat]) * u64::from(pips[seat]);
+            if winners.contains(&seat) {
+                standing.wins += 1.0 / winners.len() as f64;
+            }
+            for other in 0..players {
+                if pips[seat] < pips[other] {
+                    result.head_to_head[seating[seat]][seating[other]] += 1;
+                }
+            }
+        }
+    }
+    Ok(result)
+}